@@ -0,0 +1,41 @@
+//! 多列身份证号扫描: 逐行与并行提取的性能对比
+//!
+//! 运行: `cargo bench --features parallel --bench multi_column`
+
+use calamine::Data;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use tauri_app_lib::xlsx::{extract_id_columns_parallel, extract_id_columns_sequential};
+
+/// 农村低保模板的身份证列
+const ID_COLUMNS: [usize; 9] = [6, 15, 17, 19, 21, 23, 25, 27, 29];
+
+/// 生成模拟的农村低保名册行, 每户填写部分家庭成员
+fn sample_rows(count: usize) -> Vec<Vec<Data>> {
+    (0..count)
+        .map(|i| {
+            let mut row = vec![Data::Empty; 30];
+            for (member, &col) in ID_COLUMNS.iter().enumerate().take(1 + i % ID_COLUMNS.len()) {
+                row[col] = Data::String(format!("1101012010{:08}", i * 10 + member));
+            }
+            row
+        })
+        .collect()
+}
+
+fn bench_multi_column(c: &mut Criterion) {
+    let rows = sample_rows(50_000);
+    let rows: Vec<&[Data]> = rows.iter().map(Vec::as_slice).collect();
+
+    let mut group = c.benchmark_group("multi_column_50k");
+    group.bench_function("sequential", |b| {
+        b.iter(|| extract_id_columns_sequential(black_box(&rows), &ID_COLUMNS))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| extract_id_columns_parallel(black_box(&rows), &ID_COLUMNS))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_multi_column);
+criterion_main!(benches);
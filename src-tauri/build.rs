@@ -1,3 +1,8 @@
 fn main() {
+    // 供 get_app_info 报告目标平台
+    println!(
+        "cargo:rustc-env=TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
     tauri_build::build()
 }
@@ -1,10 +1,27 @@
 use crate::xlsx::{
-    DifficultyType, MatchResult, match_students_with_difficulty, read_difficult_type_table,
-    read_student_info,
+    self, CompatibilityReport, DifficultPerson, DifficultyDiff, DifficultyFileReport,
+    DifficultyType, ExcelError, LayoutInfo, MatchKind, MatchOptions, MatchResult, MergedRoster,
+    ReadProgress, SelfTestReport, SortKey, Student, StudentColumnConfig, WorksheetInfo,
+    anonymize_sheet, check_id_compatibility, count_data_rows, count_matching_ids,
+    dedup_difficult_people, dedup_match_results, detect_difficulty_type_candidates,
+    detect_text_encoding, find_duplicate_students, mask_id_number, mask_match_ids,
+    match_students_with_difficulty, match_students_with_difficulty_opts, merge_student_rosters,
+    parse_id_demographics, read_difficult_type_table, read_difficult_type_table_with_progress,
+    read_simple_id_list, read_student_info_with_config, read_worksheet_infos, sort_match_results,
 };
-use rust_xlsxwriter::{Format, Workbook};
+use chrono::{Local, NaiveDateTime};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Instant, SystemTime},
+};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
 
 /// 命令执行结果
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,6 +29,8 @@ pub struct CommandResult<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    #[serde(rename = "elapsedMs", default)]
+    pub elapsed_ms: Option<u64>, // 命令耗时 (毫秒), 仅部分命令填写
 }
 
 impl<T> CommandResult<T> {
@@ -20,14 +39,33 @@ impl<T> CommandResult<T> {
             success: true,
             data: Some(data),
             error: None,
+            elapsed_ms: None,
         }
     }
 
     pub fn error(error: String) -> Self {
+        tracing::warn!(error = %error, "命令执行失败");
         Self {
             success: false,
             data: None,
             error: Some(error),
+            elapsed_ms: None,
+        }
+    }
+
+    /// 将 `Result` 转换为命令执行结果, 错误信息使用其 `Display` 输出
+    pub fn from_result<E: std::fmt::Display>(result: Result<T, E>) -> Self {
+        match result {
+            Ok(data) => Self::success(data),
+            Err(e) => Self::error(e.to_string()),
+        }
+    }
+
+    /// 记录从 `start` 到现在的耗时
+    pub fn with_elapsed(self, start: Instant) -> Self {
+        Self {
+            elapsed_ms: Some(start.elapsed().as_millis() as u64),
+            ..self
         }
     }
 }
@@ -35,296 +73,2947 @@ impl<T> CommandResult<T> {
 /// 匹配结果统计信息
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MatchStatistics {
-    pub total_students: usize,
+    pub total_students: usize, // 参与匹配的学生数, 不含资料不全的学生
+    #[serde(default)]
+    pub incomplete_students: usize, // 资料不全 (缺少身份证号) 的学生数
     pub total_matches: usize,
-    pub difficulty_type_counts: std::collections::HashMap<DifficultyType, usize>,
+    pub match_rate: f64, // 匹配率 (0~1, 保留四位小数)
+    pub difficulty_type_counts: HashMap<DifficultyType, usize>,
+    pub by_grade: HashMap<String, usize>, // 按年级统计, 缺失记为 "未知"
+    pub by_school: HashMap<String, usize>, // 按学校统计, 缺失记为 "未知"
+}
+
+/// 年级、学校缺失时的统计分组名
+const UNKNOWN_GROUP: &str = "未知";
+
+impl MatchStatistics {
+    /// 根据学生名册人数与匹配结果计算统计信息
+    pub fn new(total_students: usize, matches: &[MatchResult]) -> Self {
+        // 所有困难类型都列出, 没有匹配的类型记为 0
+        let mut difficulty_type_counts: HashMap<DifficultyType, usize> = DifficultyType::all()
+            .into_iter()
+            .map(|difficulty_type| (difficulty_type, 0))
+            .collect();
+        let mut by_grade = HashMap::new();
+        let mut by_school = HashMap::new();
+
+        let group_name = |value: &Option<String>| {
+            value
+                .as_deref()
+                .filter(|v| !v.is_empty())
+                .unwrap_or(UNKNOWN_GROUP)
+                .to_string()
+        };
+
+        for match_result in matches {
+            *difficulty_type_counts
+                .entry(match_result.difficult_info.difficulty_type)
+                .or_insert(0) += 1;
+            *by_grade
+                .entry(group_name(&match_result.student.grade))
+                .or_insert(0) += 1;
+            *by_school
+                .entry(group_name(&match_result.student.school))
+                .or_insert(0) += 1;
+        }
+
+        // 名册为空时匹配率记为 0, 避免出现 NaN
+        let match_rate = if total_students == 0 {
+            0.0
+        } else {
+            (matches.len() as f64 / total_students as f64 * 10000.0).round() / 10000.0
+        };
+
+        Self {
+            total_students,
+            incomplete_students: 0,
+            total_matches: matches.len(),
+            match_rate,
+            difficulty_type_counts,
+            by_grade,
+            by_school,
+        }
+    }
+
+    /// 记录资料不全的学生数 (不影响匹配率)
+    pub fn with_incomplete_students(mut self, count: usize) -> Self {
+        self.incomplete_students = count;
+        self
+    }
 }
 
 /// 根据困难类型查找学生信息
 #[tauri::command]
 pub async fn find_students_by_difficulty(
+    app: AppHandle,
     student_file_path: String,
     difficulty_file_path: String,
     difficulty_type: String,
+    student_column_config: Option<StudentColumnConfig>,
+    match_options: Option<MatchOptions>,
+    operation_id: Option<String>,
 ) -> CommandResult<Vec<MatchResult>> {
-    // 读取学生信息
-    let students = match read_student_info(&student_file_path) {
-        Ok(students) => students,
-        Err(e) => {
-            return CommandResult::error(format!("读取学生文件失败: {}", e));
-        }
+    let start = Instant::now();
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type))
+            .with_elapsed(start);
     };
 
-    // 解析困难类型枚举
-    let difficulty_json = format!(r#""{}""#, difficulty_type);
-    let difficulty_enum: DifficultyType = match serde_json::from_str(&difficulty_json) {
-        Ok(enum_val) => enum_val,
-        Err(_) => {
-            return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
-        }
+    let result = match run_difficulty_match(
+        &app,
+        operation_id,
+        &student_file_path,
+        &difficulty_file_path,
+        difficulty_enum,
+        &student_column_config.unwrap_or_default(),
+        &match_options.unwrap_or_default(),
+        &|_| {},
+    ) {
+        Ok(run) => CommandResult::success(run.matches),
+        Err(e) => CommandResult::error(e),
     };
+    result.with_elapsed(start)
+}
 
-    // 读取困难类型表
-    let difficult_students = match read_difficult_type_table(&difficulty_file_path, difficulty_enum)
-    {
-        Ok(difficult_students) => difficult_students,
+/// 根据困难类型查找学生信息, 读取困难类型表时通过 `read-progress` 事件回报进度
+#[tauri::command]
+pub async fn find_students_by_difficulty_with_progress(
+    window: Window,
+    student_file_path: String,
+    difficulty_file_path: String,
+    difficulty_type: String,
+    student_column_config: Option<StudentColumnConfig>,
+    match_options: Option<MatchOptions>,
+    operation_id: Option<String>,
+) -> CommandResult<Vec<MatchResult>> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
+    };
+
+    // 发送失败不影响读取, 直接忽略
+    let on_progress = |progress: ReadProgress| {
+        let _ = window.emit("read-progress", progress);
+    };
+
+    match run_difficulty_match(
+        window.app_handle(),
+        operation_id,
+        &student_file_path,
+        &difficulty_file_path,
+        difficulty_enum,
+        &student_column_config.unwrap_or_default(),
+        &match_options.unwrap_or_default(),
+        &on_progress,
+    ) {
+        Ok(run) => CommandResult::success(run.matches),
+        Err(e) => CommandResult::error(e),
+    }
+}
+
+/// 根据文件内容自动识别困难类型并查找学生信息
+#[tauri::command]
+pub async fn find_students_auto(
+    app: AppHandle,
+    student_file_path: String,
+    difficulty_file_path: String,
+    operation_id: Option<String>,
+) -> CommandResult<Vec<MatchResult>> {
+    let candidates = match detect_difficulty_type_candidates(&difficulty_file_path) {
+        Ok(candidates) => candidates,
         Err(e) => {
             return CommandResult::error(format!("读取困难类型文件失败: {}", e));
         }
     };
 
-    // 匹配学生信息
-    let matches = match_students_with_difficulty(&students, &difficult_students);
+    tracing::info!(difficulty_file_path, candidates = ?candidates, "识别困难类型");
+    let difficulty_type = match candidates.as_slice() {
+        [difficulty_type] => *difficulty_type,
+        [] => return CommandResult::error("无法识别困难类型, 请手动选择".to_string()),
+        _ => {
+            let labels: Vec<String> = candidates.iter().map(|t| t.to_string()).collect();
+            return CommandResult::error(format!(
+                "无法唯一确定困难类型, 可能为: {}",
+                labels.join("、")
+            ));
+        }
+    };
+
+    match run_difficulty_match(
+        &app,
+        operation_id,
+        &student_file_path,
+        &difficulty_file_path,
+        difficulty_type,
+        &StudentColumnConfig::default(),
+        &MatchOptions::default(),
+        &|_| {},
+    ) {
+        Ok(run) => CommandResult::success(run.matches),
+        Err(e) => CommandResult::error(e),
+    }
+}
+
+/// 使用没有固定模板的身份证号清单查找学生信息
+///
+/// 清单取第一个工作表, `id_column` 为身份证号所在列 (从 0 开始), `skip_rows` 为表头占用的行数。
+#[tauri::command]
+pub async fn find_students_by_id_list(
+    app: AppHandle,
+    student_file_path: String,
+    id_list_path: String,
+    id_column: usize,
+    skip_rows: usize,
+    difficulty_type: DifficultyType,
+    student_column_config: Option<StudentColumnConfig>,
+) -> CommandResult<Vec<MatchResult>> {
+    let roster_cache = app.state::<RosterCache>();
+    let students = match roster_cache.get_or_read(
+        &student_file_path,
+        &student_column_config.unwrap_or_default(),
+    ) {
+        Ok(students) => students,
+        Err(e) => return CommandResult::error(format!("读取学生文件失败: {}", e)),
+    };
+    let people = match read_simple_id_list(&id_list_path, id_column, skip_rows, difficulty_type) {
+        Ok(people) => people,
+        Err(e) => return CommandResult::error(format!("读取身份证号清单失败: {}", e)),
+    };
 
+    let matches = match_students_with_difficulty(&students, &people);
+    tracing::info!(
+        id_list_path,
+        id_count = people.len(),
+        match_count = matches.len(),
+        "身份证号清单匹配完成"
+    );
     CommandResult::success(matches)
 }
 
-/// 获取匹配结果统计信息
+/// 估算匹配数量: 只统计名册与困难类型表身份证号的交集大小, 不生成匹配记录
 #[tauri::command]
-pub async fn get_students_match_statistics(
+pub async fn estimate_matches(
+    app: AppHandle,
     student_file_path: String,
     difficulty_file_path: String,
     difficulty_type: String,
-) -> CommandResult<MatchStatistics> {
-    // 复用查找逻辑
-    let result =
-        find_students_by_difficulty(student_file_path, difficulty_file_path, difficulty_type).await;
+    operation_id: Option<String>,
+) -> CommandResult<usize> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
+    };
+    let operations = app.state::<Operations>();
+    let cancel_token = operations.begin(operation_id);
 
-    match result {
-        CommandResult {
-            success: true,
-            data: Some(matches),
-            ..
-        } => {
-            let mut difficulty_type_counts = std::collections::HashMap::new();
-
-            for match_result in &matches {
-                *difficulty_type_counts
-                    .entry(match_result.difficult_info.difficulty_type)
-                    .or_insert(0) += 1;
-            }
+    let roster_cache = app.state::<RosterCache>();
+    let students =
+        match roster_cache.get_or_read(&student_file_path, &StudentColumnConfig::default()) {
+            Ok(students) => students,
+            Err(e) => return CommandResult::error(format!("读取学生文件失败: {}", e)),
+        };
+    let difficult_people = match read_difficult_type_table_cancellable(
+        &difficulty_file_path,
+        difficulty_enum,
+        false,
+        &cancel_token,
+        &|_| {},
+    ) {
+        Ok(people) => people,
+        Err(ExcelError::Cancelled) => {
+            return CommandResult::error(ExcelError::Cancelled.to_string());
+        }
+        Err(e) => return CommandResult::error(format!("读取困难类型文件失败: {}", e)),
+    };
 
-            let statistics = MatchStatistics {
-                total_students: matches.len(),
-                total_matches: matches.len(),
-                difficulty_type_counts,
-            };
+    let estimate = count_matching_ids(&students, &difficult_people);
+    tracing::info!(
+        student_count = students.len(),
+        difficult_count = difficult_people.len(),
+        estimate,
+        "估算匹配数量"
+    );
+    CommandResult::success(estimate)
+}
+
+/// 抽样对比名册与困难类型表的身份证号形态 (18 位、15 位、遮挡), 在匹配前发现格式不一致
+#[tauri::command]
+pub async fn check_id_format_compatibility(
+    app: AppHandle,
+    student_file_path: String,
+    difficulty_file_path: String,
+    difficulty_type: String,
+) -> CommandResult<CompatibilityReport> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
+    };
+
+    let roster_cache = app.state::<RosterCache>();
+    let students =
+        match roster_cache.get_or_read(&student_file_path, &StudentColumnConfig::default()) {
+            Ok(students) => students,
+            Err(e) => return CommandResult::error(format!("读取学生文件失败: {}", e)),
+        };
+    let difficult_people = match read_difficult_type_table(&difficulty_file_path, difficulty_enum) {
+        Ok(people) => people,
+        Err(e) => return CommandResult::error(format!("读取困难类型文件失败: {}", e)),
+    };
+
+    CommandResult::success(check_id_compatibility(&students, &difficult_people))
+}
+
+/// 学生名册缓存的键: 文件路径、修改时间及列配置
+type RosterCacheKey = (PathBuf, SystemTime, StudentColumnConfig);
+
+/// 已解析的学生名册缓存, 同一名册对多个困难类型重复匹配时无需重新解析
+#[derive(Default)]
+pub struct RosterCache {
+    entries: Mutex<HashMap<RosterCacheKey, Arc<Vec<Student>>>>,
+}
 
-            CommandResult::success(statistics)
+impl RosterCache {
+    /// 读取学生信息表, 文件修改时间未变化时直接返回缓存结果
+    pub fn get_or_read(
+        &self,
+        file_path: &str,
+        config: &StudentColumnConfig,
+    ) -> Result<Arc<Vec<Student>>, ExcelError> {
+        let (path, modified) = file_stamp(file_path)?;
+        let key = (path, modified, config.clone());
+
+        if let Some(students) = self.lock().get(&key) {
+            return Ok(Arc::clone(students));
         }
-        CommandResult {
-            error: Some(err), ..
-        } => CommandResult::error(err),
-        _ => CommandResult::error("获取统计信息失败".to_string()),
+
+        let students = Arc::new(read_student_info_with_config(file_path, config)?);
+        let mut entries = self.lock();
+        // 同一文件修改前的缓存已失效
+        entries.retain(|(path, modified, _), _| *path != key.0 || *modified == key.1);
+        entries.insert(key, Arc::clone(&students));
+        Ok(students)
+    }
+
+    /// 清空缓存, 返回清除的条目数
+    pub fn clear(&self) -> usize {
+        let mut entries = self.lock();
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<RosterCacheKey, Arc<Vec<Student>>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
-/// 验证上传的文件
-#[tauri::command]
-pub async fn validate_uploaded_file(file_path: String) -> CommandResult<FileInfo> {
-    let path = PathBuf::from(&file_path);
+/// 文件路径及其修改时间, 用于判断缓存是否失效
+fn file_stamp(file_path: &str) -> Result<(PathBuf, SystemTime), ExcelError> {
+    let path = PathBuf::from(file_path);
+    let modified = std::fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|_| ExcelError::FileNotFound(file_path.to_string()))?;
+    Ok((path, modified))
+}
 
-    if !path.exists() {
-        return CommandResult::error("文件不存在".to_string());
+/// 匹配结果缓存的键: 两个文件及其修改时间、困难类型、列配置和匹配选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MatchCacheKey {
+    student_file: (PathBuf, SystemTime),
+    difficulty_file: (PathBuf, SystemTime),
+    difficulty_type: DifficultyType,
+    column_config: StudentColumnConfig,
+    match_options: MatchOptions,
+}
+
+/// 最近一次分页查询的完整匹配结果, 翻页时无需重新匹配
+#[derive(Default)]
+pub struct MatchCache {
+    latest: Mutex<Option<(MatchCacheKey, Arc<Vec<MatchResult>>)>>,
+}
+
+impl MatchCache {
+    /// 键与最近一次查询相同时返回缓存结果, 否则执行匹配并替换缓存
+    fn get_or_run(
+        &self,
+        key: MatchCacheKey,
+        run: impl FnOnce() -> Result<Vec<MatchResult>, String>,
+    ) -> Result<Arc<Vec<MatchResult>>, String> {
+        if let Some((cached_key, matches)) = self.lock().as_ref()
+            && *cached_key == key
+        {
+            return Ok(Arc::clone(matches));
+        }
+
+        let matches = Arc::new(run()?);
+        *self.lock() = Some((key, Arc::clone(&matches)));
+        Ok(matches)
     }
 
-    let file_name = path
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
+    fn lock(&self) -> MutexGuard<'_, Option<(MatchCacheKey, Arc<Vec<MatchResult>>)>> {
+        self.latest.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
 
-    let file_extension = path
-        .extension()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string()
-        .to_lowercase();
+/// 一页匹配结果及结果总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PagedMatches {
+    pub items: Vec<MatchResult>,
+    pub total: usize,
+}
 
-    if file_extension != "xlsx" && file_extension != "xls" {
-        return CommandResult::error("仅支持 Excel 文件 (.xlsx 或 .xls)".to_string());
+impl PagedMatches {
+    /// 取出从 `offset` 开始的至多 `limit` 条结果, 超出范围时返回空页
+    fn from_slice(matches: &[MatchResult], offset: usize, limit: usize) -> Self {
+        Self {
+            items: matches.iter().skip(offset).take(limit).cloned().collect(),
+            total: matches.len(),
+        }
     }
+}
 
-    let file_size = match std::fs::metadata(&path) {
-        Ok(metadata) => metadata.len(),
-        Err(e) => return CommandResult::error(format!("无法读取文件信息: {}", e)),
+/// 分页查找学生信息, 完整结果缓存在后端, 翻页时不重新匹配
+#[tauri::command]
+#[allow(clippy::too_many_arguments)] // 与 find_students_by_difficulty 一致, 另加分页参数
+pub async fn find_students_paged(
+    app: AppHandle,
+    student_file_path: String,
+    difficulty_file_path: String,
+    difficulty_type: String,
+    student_column_config: Option<StudentColumnConfig>,
+    match_options: Option<MatchOptions>,
+    offset: usize,
+    limit: usize,
+) -> CommandResult<PagedMatches> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
     };
+    let column_config = student_column_config.unwrap_or_default();
+    let match_options = match_options.unwrap_or_default();
 
-    let file_info = FileInfo {
-        name: file_name,
-        path: file_path,
-        size: file_size,
-        extension: file_extension,
+    let key = match (
+        file_stamp(&student_file_path),
+        file_stamp(&difficulty_file_path),
+    ) {
+        (Ok(student_file), Ok(difficulty_file)) => MatchCacheKey {
+            student_file,
+            difficulty_file,
+            difficulty_type: difficulty_enum,
+            column_config: column_config.clone(),
+            match_options: match_options.clone(),
+        },
+        (Err(e), _) => return CommandResult::error(format!("读取学生文件失败: {}", e)),
+        (_, Err(e)) => return CommandResult::error(format!("读取困难类型文件失败: {}", e)),
     };
 
-    CommandResult::success(file_info)
+    let matches = app.state::<MatchCache>().get_or_run(key, || {
+        run_difficulty_match(
+            &app,
+            None,
+            &student_file_path,
+            &difficulty_file_path,
+            difficulty_enum,
+            &column_config,
+            &match_options,
+            &|_| {},
+        )
+        .map(|run| run.matches)
+    });
+
+    match matches {
+        Ok(matches) => CommandResult::success(PagedMatches::from_slice(&matches, offset, limit)),
+        Err(e) => CommandResult::error(e),
+    }
+}
+
+/// 按年级、班级、学校筛选匹配结果 (条件同时满足, 忽略首尾空白和大小写)
+#[tauri::command]
+pub async fn filter_matches(
+    matches: Vec<MatchResult>,
+    grade: Option<String>,
+    class: Option<String>,
+    school: Option<String>,
+) -> CommandResult<Vec<MatchResult>> {
+    CommandResult::success(xlsx::filter_match_results(
+        matches,
+        grade.as_deref(),
+        class.as_deref(),
+        school.as_deref(),
+    ))
 }
 
-/// 获取困难类型选项
+/// 检查学生信息表中身份证号重复的记录, 便于在匹配前提示用户
 #[tauri::command]
-pub async fn get_difficulty_type_options() -> CommandResult<Vec<DifficultyTypeOption>> {
-    let options = DifficultyType::all()
-        .into_iter()
-        .map(|difficulty_type| DifficultyTypeOption {
-            label: difficulty_type.to_string(),
-            value: difficulty_type.to_string(),
-        })
-        .collect();
+pub async fn find_duplicate_students_in_file(
+    app: AppHandle,
+    student_file_path: String,
+    student_column_config: Option<StudentColumnConfig>,
+) -> CommandResult<Vec<(String, Vec<Student>)>> {
+    let roster_cache = app.state::<RosterCache>();
+    match roster_cache.get_or_read(
+        &student_file_path,
+        &student_column_config.unwrap_or_default(),
+    ) {
+        Ok(students) => CommandResult::success(find_duplicate_students(&students)),
+        Err(e) => CommandResult::error(format!("读取学生文件失败: {}", e)),
+    }
+}
 
-    CommandResult::success(options)
+/// 清空学生名册缓存, 在外部修改名册后强制重新读取
+#[tauri::command]
+pub fn clear_roster_cache(state: State<'_, RosterCache>) -> CommandResult<usize> {
+    let count = state.clear();
+    tracing::info!(count, "清空学生名册缓存");
+    CommandResult::success(count)
 }
 
-/// 文件信息结构
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FileInfo {
-    pub name: String,
-    pub path: String,
-    pub size: u64,
-    pub extension: String,
+/// 单个长时间操作的取消标记
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// 已取消时返回错误信息
+    fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(ExcelError::Cancelled.to_string())
+        } else {
+            Ok(())
+        }
+    }
 }
 
-/// 困难类型选项
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DifficultyTypeOption {
-    pub label: String,
-    pub value: String,
+/// 正在进行的长时间操作, 每个操作有独立的取消标记
+#[derive(Default)]
+pub struct Operations {
+    next_id: AtomicUsize,
+    running: Mutex<HashMap<String, CancellationToken>>,
 }
 
-/// 导出匹配结果到 Excel 文件
-#[tauri::command]
-pub async fn export_matches_to_excel(
-    matches: Vec<MatchResult>,
-    output_path: String,
-) -> CommandResult<String> {
-    match create_excel_report(&matches, &output_path) {
-        Ok(_) => CommandResult::success(output_path),
-        Err(e) => CommandResult::error(format!("导出 Excel 失败: {}", e)),
+impl Operations {
+    /// 登记一个新操作, 前端未指定 ID 时自动生成; 返回的守卫在操作结束时注销该操作
+    pub fn begin(&self, operation_id: Option<String>) -> OperationGuard<'_> {
+        let id = operation_id
+            .unwrap_or_else(|| format!("auto-{}", self.next_id.fetch_add(1, Ordering::Relaxed)));
+        let token = CancellationToken::default();
+        self.lock().insert(id.clone(), token.clone());
+        OperationGuard {
+            operations: self,
+            id,
+            token,
+        }
+    }
+
+    /// 取消指定的操作, 未指定时取消全部正在进行的操作; 返回被取消的操作数
+    pub fn cancel(&self, operation_id: Option<&str>) -> usize {
+        let running = self.lock();
+        let tokens: Vec<&CancellationToken> = match operation_id {
+            Some(id) => running.get(id).into_iter().collect(),
+            None => running.values().collect(),
+        };
+        tokens.iter().for_each(|token| token.cancel());
+        tokens.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CancellationToken>> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
-/// 创建 Excel 报告
-fn create_excel_report(
-    matches: &[MatchResult],
-    output_path: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut workbook = Workbook::new();
-    let worksheet = workbook.add_worksheet();
+/// 正在进行的操作, 离开作用域时从 [`Operations`] 中注销
+pub struct OperationGuard<'a> {
+    operations: &'a Operations,
+    id: String,
+    token: CancellationToken,
+}
 
-    // 设置标题格式
-    let header_format = Format::new()
-        .set_bold()
-        .set_background_color("#4CAF50")
-        .set_font_color("#FFFFFF")
-        .set_align(rust_xlsxwriter::FormatAlign::Center);
+impl std::ops::Deref for OperationGuard<'_> {
+    type Target = CancellationToken;
 
-    // 写入标题行
-    let headers = [
-        "序号",
-        "学生姓名",
-        "身份证号",
-        "学号",
-        "班级",
-        "年级",
-        "学校",
-        "困难类型",
-    ];
+    fn deref(&self) -> &CancellationToken {
+        &self.token
+    }
+}
 
-    for (col, header) in headers.iter().enumerate() {
-        worksheet.write_with_format(0, col as u16, *header, &header_format)?;
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        let mut running = self.operations.lock();
+        // 前端可能复用同一 ID 开始了新操作, 只注销自己登记的标记
+        if running
+            .get(&self.id)
+            .is_some_and(|token| Arc::ptr_eq(&token.0, &self.token.0))
+        {
+            running.remove(&self.id);
+        }
     }
+}
+
+/// 取消正在进行的匹配或读取操作
+///
+/// 传入 `operation_id` 时只取消对应的操作, 否则取消全部正在进行的操作。
+#[tauri::command]
+pub async fn cancel_current_operation(
+    app: AppHandle,
+    operation_id: Option<String>,
+) -> CommandResult<usize> {
+    let count = app.state::<Operations>().cancel(operation_id.as_deref());
+    tracing::info!(operation_id, count, "请求取消操作");
+    CommandResult::success(count)
+}
 
-    // 设置数据格式
-    let data_format = Format::new().set_align(rust_xlsxwriter::FormatAlign::Left);
-    let number_format = Format::new().set_align(rust_xlsxwriter::FormatAlign::Center);
+/// 读取困难类型表, 读取过程中定期检查取消标记
+fn read_difficult_type_table_cancellable(
+    file_path: &str,
+    difficulty_type: DifficultyType,
+    strict: bool,
+    cancel_token: &CancellationToken,
+    on_progress: &dyn Fn(ReadProgress),
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    read_difficult_type_table_with_progress(file_path, difficulty_type, strict, &|progress| {
+        on_progress(progress);
+        !cancel_token.is_cancelled()
+    })
+}
 
-    // 写入数据
-    for (row, match_result) in matches.iter().enumerate() {
-        let row = row + 1; // 跳过标题行
+/// 一次匹配的结果及学生名册人数
+struct MatchRun {
+    total_students: usize, // 参与匹配的学生数, 不含资料不全的学生
+    matches: Vec<MatchResult>,
+    incomplete_students: Vec<Student>, // 资料不全的学生, 仅在列配置保留时存在
+}
 
-        worksheet.write_with_format(row as u32, 0, row as u32, &number_format)?;
-        worksheet.write_with_format(row as u32, 1, &match_result.student.name, &data_format)?;
-        worksheet.write_with_format(
-            row as u32,
-            2,
-            &match_result.student.id_number,
-            &data_format,
-        )?;
-        worksheet.write_with_format(
-            row as u32,
-            3,
-            match_result.student.student_id.as_deref().unwrap_or(""),
-            &data_format,
-        )?;
-        worksheet.write_with_format(
-            row as u32,
-            4,
-            match_result.student.class.as_deref().unwrap_or(""),
-            &data_format,
-        )?;
-        worksheet.write_with_format(
-            row as u32,
-            5,
-            match_result.student.grade.as_deref().unwrap_or(""),
-            &data_format,
-        )?;
-        worksheet.write_with_format(
-            row as u32,
-            6,
-            match_result.student.school.as_deref().unwrap_or(""),
-            &data_format,
-        )?;
-        worksheet.write_with_format(
-            row as u32,
-            7,
-            match_result.difficult_info.difficulty_type.to_string(),
-            &data_format,
-        )?;
-    }
+/// 读取学生信息表与困难类型表并进行匹配, `operation_id` 用于单独取消本次匹配
+#[allow(clippy::too_many_arguments)] // 与匹配命令的参数一一对应
+fn run_difficulty_match(
+    app: &AppHandle,
+    operation_id: Option<String>,
+    student_file_path: &str,
+    difficulty_file_path: &str,
+    difficulty_type: DifficultyType,
+    column_config: &StudentColumnConfig,
+    match_options: &MatchOptions,
+    on_progress: &dyn Fn(ReadProgress),
+) -> Result<MatchRun, String> {
+    tracing::info!(
+        student_file_path,
+        difficulty_file_path,
+        %difficulty_type,
+        fuzzy = match_options.fuzzy,
+        strict = match_options.strict,
+        "开始匹配"
+    );
+    let operations = app.state::<Operations>();
+    let cancel_token = operations.begin(operation_id);
 
-    // 设置列宽
-    worksheet.set_column_width(0, 6.0)?; // 序号
-    worksheet.set_column_width(1, 12.0)?; // 姓名
-    worksheet.set_column_width(2, 20.0)?; // 身份证号
-    worksheet.set_column_width(3, 15.0)?; // 学号
-    worksheet.set_column_width(4, 12.0)?; // 班级
-    worksheet.set_column_width(5, 8.0)?; // 年级
-    worksheet.set_column_width(6, 20.0)?; // 学校
-    worksheet.set_column_width(7, 18.0)?; // 困难类型
+    // 读取学生信息 (名册未修改时使用缓存)
+    let roster_cache = app.state::<RosterCache>();
+    let students = match roster_cache.get_or_read(student_file_path, column_config) {
+        Ok(students) => students,
+        Err(e) => {
+            return Err(format!("读取学生文件失败: {}", e));
+        }
+    };
+    tracing::info!(student_count = students.len(), "读取学生信息完成");
+    cancel_token.check()?;
 
-    // 添加统计信息工作表
-    let stats_worksheet = workbook.add_worksheet();
-    stats_worksheet.set_name("统计信息")?;
+    // 读取困难类型表
+    let difficult_students = match read_difficult_type_table_cancellable(
+        difficulty_file_path,
+        difficulty_type,
+        match_options.strict,
+        &cancel_token,
+        on_progress,
+    ) {
+        Ok(difficult_students) => difficult_students,
+        Err(ExcelError::Cancelled) => return Err(ExcelError::Cancelled.to_string()),
+        Err(e) => {
+            return Err(format!("读取困难类型文件失败: {}", e));
+        }
+    };
+    tracing::info!(
+        difficult_count = difficult_students.len(),
+        "读取困难类型表完成"
+    );
 
-    // 写入统计信息标题
-    stats_worksheet.write_with_format(0, 0, "统计项目", &header_format)?;
-    stats_worksheet.write_with_format(0, 1, "数量", &header_format)?;
+    // 匹配学生信息
+    let matches =
+        match_students_with_difficulty_opts(&students, &difficult_students, match_options);
+    tracing::info!(match_count = matches.len(), "匹配完成");
 
-    // 计算统计信息
-    let mut difficulty_counts = std::collections::HashMap::new();
-    for match_result in matches {
-        *difficulty_counts
-            .entry(match_result.difficult_info.difficulty_type.to_string())
-            .or_insert(0) += 1;
-    }
+    let incomplete_students: Vec<Student> = students
+        .iter()
+        .filter(|student| student.incomplete)
+        .cloned()
+        .collect();
+    Ok(MatchRun {
+        total_students: students.len() - incomplete_students.len(),
+        matches,
+        incomplete_students,
+    })
+}
 
-    let mut row = 1;
-    stats_worksheet.write_with_format(row as u32, 0, "总匹配数量", &data_format)?;
-    stats_worksheet.write_with_format(row as u32, 1, matches.len() as u32, &number_format)?;
-    row += 1;
+/// 困难类型文件输入 (文件路径与对应的困难类型)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DifficultyFileInput {
+    pub file_path: String,
+    pub difficulty_type: DifficultyType,
+}
 
-    stats_worksheet.write_with_format(row as u32, 0, "按困难类型分布:", &data_format)?;
-    row += 1;
+/// 同时按多个困难类型文件查找学生信息
+///
+/// 同一学生可以对应多条不同困难类型的匹配结果, 相同的 (身份证号, 困难类型) 只保留一条。
+#[tauri::command]
+pub async fn find_students_multi_difficulty(
+    app: AppHandle,
+    student_file_path: String,
+    difficulty_files: Vec<DifficultyFileInput>,
+    operation_id: Option<String>,
+) -> CommandResult<Vec<MatchResult>> {
+    tracing::info!(
+        student_file_path,
+        file_count = difficulty_files.len(),
+        "开始多类型匹配"
+    );
+    let operations = app.state::<Operations>();
+    let cancel_token = operations.begin(operation_id);
 
-    for (difficulty_type, count) in difficulty_counts.iter() {
-        stats_worksheet.write_with_format(row as u32, 0, difficulty_type, &data_format)?;
-        stats_worksheet.write_with_format(row as u32, 1, *count as u32, &number_format)?;
-        row += 1;
+    // 读取学生信息 (名册未修改时使用缓存)
+    let roster_cache = app.state::<RosterCache>();
+    let students =
+        match roster_cache.get_or_read(&student_file_path, &StudentColumnConfig::default()) {
+            Ok(students) => students,
+            Err(e) => {
+                return CommandResult::error(format!("读取学生文件失败: {}", e));
+            }
+        };
+    tracing::info!(student_count = students.len(), "读取学生信息完成");
+
+    // 依次读取各困难类型表并合并
+    let mut difficult_students = Vec::new();
+    for input in &difficulty_files {
+        match read_difficult_type_table_cancellable(
+            &input.file_path,
+            input.difficulty_type,
+            false,
+            &cancel_token,
+            &|_| {},
+        ) {
+            Ok(people) => difficult_students.extend(people),
+            Err(ExcelError::Cancelled) => {
+                return CommandResult::error(ExcelError::Cancelled.to_string());
+            }
+            Err(e) => {
+                return CommandResult::error(format!(
+                    "读取困难类型文件失败 ({}): {}",
+                    input.file_path, e
+                ));
+            }
+        }
     }
+    let difficult_students = dedup_difficult_people(difficult_students);
+    tracing::info!(
+        difficult_count = difficult_students.len(),
+        "读取困难类型表完成"
+    );
 
-    // 设置统计表列宽
-    stats_worksheet.set_column_width(0, 25.0)?;
-    stats_worksheet.set_column_width(1, 10.0)?;
+    // 匹配学生信息
+    let matches = match_students_with_difficulty(&students, &difficult_students);
+    tracing::info!(match_count = matches.len(), "匹配完成");
 
-    workbook.save(output_path)?;
-    Ok(())
+    CommandResult::success(matches)
+}
+
+/// 文件夹中单个文件的处理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMatchResult {
+    pub file_path: String,
+    pub file_name: String,
+    pub difficulty_type: Option<DifficultyType>, // 识别出的困难类型
+    pub record_count: usize,                     // 读取到的困难人员数
+    pub match_count: usize,                      // 该文件匹配到的学生数
+    pub error: Option<String>,                   // 未处理的原因, 为空表示已处理
+}
+
+/// 按文件夹批量匹配的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderMatchResult {
+    pub matches: Vec<MatchResult>,
+    pub files: Vec<FileMatchResult>,
+}
+
+/// 文件夹匹配进度, 通过 `folder-progress` 事件发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderProgress {
+    pub current_file: String,
+    pub index: usize, // 当前文件序号, 从 1 开始
+    pub total: usize,
+}
+
+/// 列出文件夹中的 Excel 文件 (不含子目录及 Office 临时文件), 按路径排序
+fn list_excel_files(folder: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let is_excel = path.extension().is_some_and(|ext| {
+                ALLOWED_EXTENSIONS
+                    .iter()
+                    .any(|allowed| ext.eq_ignore_ascii_case(allowed))
+            });
+            let is_temp = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("~$"));
+            is_excel && !is_temp
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// 读取文件夹中的一个困难类型文件: 先按文件名推断类型, 再按文件内容识别
+fn read_folder_file(
+    path: &str,
+    cancel_token: &CancellationToken,
+) -> Result<(FileMatchResult, Vec<DifficultPerson>), ExcelError> {
+    let mut status = FileMatchResult {
+        file_path: path.to_string(),
+        file_name: Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        difficulty_type: None,
+        record_count: 0,
+        match_count: 0,
+        error: None,
+    };
+
+    let Some(difficulty_type) = xlsx::guess_difficulty_type_from_file_name(path)
+        .or_else(|| xlsx::detect_difficulty_type(path))
+    else {
+        status.error = Some("无法识别困难类型".to_string());
+        return Ok((status, Vec::new()));
+    };
+    status.difficulty_type = Some(difficulty_type);
+
+    match read_difficult_type_table_cancellable(path, difficulty_type, false, cancel_token, &|_| {})
+    {
+        Ok(people) => {
+            status.record_count = people.len();
+            Ok((status, people))
+        }
+        Err(ExcelError::Cancelled) => Err(ExcelError::Cancelled),
+        Err(e) => {
+            status.error = Some(format!("读取失败: {}", e));
+            Ok((status, Vec::new()))
+        }
+    }
+}
+
+/// 对文件夹中的全部困难类型文件进行匹配, 并返回每个文件的处理情况
+///
+/// 困难类型按文件名关键字推断, 推断不出时按文件内容识别; 无法识别或读取失败的文件会被跳过。
+/// 开始读取每个文件前发送 `folder-progress` 事件。
+#[tauri::command]
+pub async fn match_folder(
+    app: AppHandle,
+    student_file_path: String,
+    folder: String,
+    operation_id: Option<String>,
+) -> CommandResult<FolderMatchResult> {
+    let paths = match list_excel_files(Path::new(&folder)) {
+        Ok(paths) => paths,
+        Err(e) => return CommandResult::error(format!("无法读取文件夹 ({}): {}", folder, e)),
+    };
+    let operations = app.state::<Operations>();
+    let cancel_token = operations.begin(operation_id);
+
+    let roster_cache = app.state::<RosterCache>();
+    let students =
+        match roster_cache.get_or_read(&student_file_path, &StudentColumnConfig::default()) {
+            Ok(students) => students,
+            Err(e) => {
+                return CommandResult::error(format!("读取学生文件失败: {}", e));
+            }
+        };
+
+    let student_path = Path::new(&student_file_path);
+    let paths: Vec<&PathBuf> = paths
+        .iter()
+        .filter(|path| path.as_path() != student_path)
+        .collect();
+    let mut files = Vec::new();
+    // 身份证号 -> 出现过该人员的 (困难类型, 文件序号), 用于统计每个文件的匹配数
+    let mut files_by_id: HashMap<String, Vec<(DifficultyType, usize)>> = HashMap::new();
+    let mut difficult_students = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        let path = path.to_string_lossy();
+        // 发送失败不影响读取, 直接忽略
+        let _ = app.emit(
+            "folder-progress",
+            FolderProgress {
+                current_file: path.to_string(),
+                index: index + 1,
+                total: paths.len(),
+            },
+        );
+
+        let (status, people) = match read_folder_file(&path, &cancel_token) {
+            Ok(result) => result,
+            Err(e) => return CommandResult::error(e.to_string()),
+        };
+        for person in &people {
+            let entries = files_by_id.entry(person.id_number.clone()).or_default();
+            if !entries.contains(&(person.difficulty_type, index)) {
+                entries.push((person.difficulty_type, index));
+            }
+        }
+        files.push(status);
+        difficult_students.extend(people);
+    }
+
+    let difficult_students = dedup_difficult_people(difficult_students);
+    let matches = match_students_with_difficulty(&students, &difficult_students);
+    for m in &matches {
+        let info = &m.difficult_info;
+        let Some(entries) = files_by_id.get(info.id_number.as_str()) else {
+            continue;
+        };
+        for &(difficulty_type, index) in entries {
+            if difficulty_type == info.difficulty_type {
+                files[index].match_count += 1;
+            }
+        }
+    }
+    tracing::info!(
+        folder,
+        file_count = files.len(),
+        skipped = files.iter().filter(|f| f.error.is_some()).count(),
+        match_count = matches.len(),
+        "文件夹匹配完成"
+    );
+
+    CommandResult::success(FolderMatchResult { matches, files })
+}
+
+/// 统计多个困难类型文件中各类型的困难人员数量 (按身份证号去重, 不需要学生名册)
+#[tauri::command]
+pub async fn summarize_difficulty_files(
+    files: Vec<DifficultyFileInput>,
+) -> CommandResult<HashMap<DifficultyType, usize>> {
+    let mut difficult_people = Vec::new();
+    for input in &files {
+        match read_difficult_type_table(&input.file_path, input.difficulty_type) {
+            Ok(people) => difficult_people.extend(people),
+            Err(e) => {
+                return CommandResult::error(format!(
+                    "读取困难类型文件失败 ({}): {}",
+                    input.file_path, e
+                ));
+            }
+        }
+    }
+
+    let mut counts = HashMap::new();
+    for person in dedup_difficult_people(difficult_people) {
+        *counts.entry(person.difficulty_type).or_insert(0) += 1;
+    }
+    tracing::info!(file_count = files.len(), counts = ?counts, "困难人员汇总完成");
+
+    CommandResult::success(counts)
+}
+
+/// 获取困难类型模板的预期布局 (工作表、数据开始行、身份证列)
+#[tauri::command]
+pub async fn get_difficulty_type_layout(difficulty_type: String) -> CommandResult<LayoutInfo> {
+    match DifficultyType::from_label(&difficulty_type) {
+        Some(difficulty_enum) => CommandResult::success(difficulty_enum.layout()),
+        None => CommandResult::error(format!("未知的困难类型: {}", difficulty_type)),
+    }
+}
+
+/// 按困难类型的模板配置自检文件, 报告每个身份证列读出的号码数量及没有读出号码的列
+#[tauri::command]
+pub async fn selftest_difficulty_file(
+    file_path: String,
+    difficulty_type: DifficultyType,
+) -> CommandResult<SelfTestReport> {
+    let result = xlsx::selftest_difficulty_file(&file_path, difficulty_type)
+        .inspect(|report| {
+            tracing::info!(
+                file_path,
+                %difficulty_type,
+                empty_columns = ?report.empty_columns,
+                "困难类型文件自检完成"
+            );
+        })
+        .map_err(|e| format!("自检失败: {}", e));
+    CommandResult::from_result(result)
+}
+
+/// 比较新旧两期困难类型文件, 返回新增和移除的身份证号
+#[tauri::command]
+pub async fn diff_difficulty_files(
+    old_path: String,
+    new_path: String,
+    difficulty_type: DifficultyType,
+) -> CommandResult<DifficultyDiff> {
+    let old = match read_difficult_type_table(&old_path, difficulty_type) {
+        Ok(people) => people,
+        Err(e) => return CommandResult::error(format!("读取旧名单失败 ({}): {}", old_path, e)),
+    };
+    let new = match read_difficult_type_table(&new_path, difficulty_type) {
+        Ok(people) => people,
+        Err(e) => return CommandResult::error(format!("读取新名单失败 ({}): {}", new_path, e)),
+    };
+
+    let diff = xlsx::diff_difficult_people(&old, &new);
+    tracing::info!(
+        added = diff.added.len(),
+        removed = diff.removed.len(),
+        unchanged = diff.unchanged_count,
+        "困难名单比较完成"
+    );
+
+    CommandResult::success(diff)
+}
+
+/// 获取匹配结果统计信息
+#[tauri::command]
+pub async fn get_students_match_statistics(
+    app: AppHandle,
+    student_file_path: String,
+    difficulty_file_path: String,
+    difficulty_type: String,
+    student_column_config: Option<StudentColumnConfig>,
+    match_options: Option<MatchOptions>,
+) -> CommandResult<MatchStatistics> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
+    };
+
+    // 复用查找逻辑
+    match run_difficulty_match(
+        &app,
+        None,
+        &student_file_path,
+        &difficulty_file_path,
+        difficulty_enum,
+        &student_column_config.unwrap_or_default(),
+        &match_options.unwrap_or_default(),
+        &|_| {},
+    ) {
+        Ok(run) => CommandResult::success(
+            MatchStatistics::new(run.total_students, &run.matches)
+                .with_incomplete_students(run.incomplete_students.len()),
+        ),
+        Err(e) => CommandResult::error(e),
+    }
+}
+
+/// 验证上传的文件
+#[tauri::command]
+pub async fn validate_uploaded_file(
+    file_path: String,
+    max_size_bytes: Option<u64>,
+) -> CommandResult<FileInfo> {
+    CommandResult::from_result(inspect_uploaded_file(
+        file_path,
+        max_size_bytes.unwrap_or(MAX_UPLOAD_SIZE_BYTES),
+    ))
+}
+
+/// 上传文件的默认大小上限 (50 MB)
+const MAX_UPLOAD_SIZE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 支持读取的 Excel 文件扩展名 (小写)
+const ALLOWED_EXTENSIONS: [&str; 4] = ["xlsx", "xlsm", "xls", "ods"];
+
+/// 学生名册额外支持的分隔符文本扩展名 (小写)
+const TEXT_ROSTER_EXTENSIONS: [&str; 2] = ["txt", "csv"];
+
+/// 检查上传文件是否存在、为 Excel 文件且不超过大小上限, 返回文件信息
+fn inspect_uploaded_file(file_path: String, max_size_bytes: u64) -> Result<FileInfo, String> {
+    let path = PathBuf::from(&file_path);
+
+    if !path.exists() {
+        return Err("文件不存在".to_string());
+    }
+
+    let file_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let file_extension = path
+        .extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+        .to_lowercase();
+
+    if !ALLOWED_EXTENSIONS.contains(&file_extension.as_str())
+        && !TEXT_ROSTER_EXTENSIONS.contains(&file_extension.as_str())
+    {
+        return Err(
+            "仅支持 Excel 文件 (.xlsx、.xlsm 或 .xls)、OpenDocument 表格 (.ods) 或分隔符文本 (.txt、.csv)".to_string(),
+        );
+    }
+
+    let file_size = std::fs::metadata(&path)
+        .map_err(|e| format!("无法读取文件信息: {}", e))?
+        .len();
+
+    if file_size > max_size_bytes {
+        return Err(format!(
+            "文件过大 (超过 {}), 当前大小 {}",
+            format_bytes(max_size_bytes),
+            format_bytes(file_size)
+        ));
+    }
+
+    // 打开第一个工作表, 避免空表或只有表头的文件通过校验
+    let data_row_count =
+        count_data_rows(&file_path, 0).map_err(|e| format!("无法读取 Excel 文件: {}", e))?;
+    if data_row_count == 0 {
+        return Err("文件中没有数据行 (仅有表头或为空)".to_string());
+    }
+
+    let encoding = if TEXT_ROSTER_EXTENSIONS.contains(&file_extension.as_str()) {
+        Some(detect_text_encoding(&file_path).map_err(|e| format!("无法识别文件编码: {}", e))?)
+    } else {
+        None
+    };
+
+    Ok(FileInfo {
+        name: file_name,
+        path: file_path,
+        size: file_size,
+        extension: file_extension,
+        data_row_count: Some(data_row_count),
+        encoding,
+    })
+}
+
+/// 读取学生信息表并返回解析后的名册, 供匹配前预览核对
+///
+/// 列配置保留资料不全的行时, 这些学生带有 `incomplete` 标记, 可单独列出。
+#[tauri::command]
+pub async fn get_students(
+    student_file_path: String,
+    student_column_config: Option<StudentColumnConfig>,
+) -> CommandResult<Vec<Student>> {
+    let result = read_student_info_with_config(
+        &student_file_path,
+        &student_column_config.unwrap_or_default(),
+    )
+    .inspect(|students| {
+        tracing::info!(
+            student_file_path,
+            student_count = students.len(),
+            "读取学生名册完成"
+        );
+    })
+    .map_err(|e| format!("读取学生文件失败: {}", e));
+    CommandResult::from_result(result)
+}
+
+/// 合并多个学生信息表, 返回去重后的名册及姓名冲突的身份证号
+#[tauri::command]
+pub async fn merge_student_files(student_file_paths: Vec<String>) -> CommandResult<MergedRoster> {
+    match merge_student_rosters(&student_file_paths) {
+        Ok(roster) => {
+            tracing::info!(
+                file_count = student_file_paths.len(),
+                student_count = roster.students.len(),
+                conflict_count = roster.conflicting_ids.len(),
+                "合并学生名册完成"
+            );
+            CommandResult::success(roster)
+        }
+        Err(e) => CommandResult::error(format!("读取学生文件失败: {}", e)),
+    }
+}
+
+/// 按困难类型的模板布局预检困难人员文件, 统计疑似身份证号数量
+#[tauri::command]
+pub async fn validate_difficulty_file(
+    file_path: String,
+    difficulty_type: String,
+) -> CommandResult<DifficultyFileReport> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
+    };
+
+    match xlsx::validate_difficulty_file(&file_path, difficulty_enum) {
+        Ok(report) => CommandResult::success(report),
+        Err(e) => CommandResult::error(format!("读取困难类型文件失败: {}", e)),
+    }
+}
+
+/// 预览工作表的前若干行, 便于在匹配前确认列位置
+#[tauri::command]
+pub async fn preview_sheet(
+    file_path: String,
+    sheet_index: usize,
+    rows: usize,
+) -> CommandResult<Vec<Vec<String>>> {
+    CommandResult::from_result(
+        xlsx::preview_sheet(&file_path, sheet_index, rows)
+            .map_err(|e| format!("读取工作表失败: {}", e)),
+    )
+}
+
+/// 列出 Excel 文件中的所有工作表
+#[tauri::command]
+pub async fn list_worksheets(file_path: String) -> CommandResult<Vec<WorksheetInfo>> {
+    match read_worksheet_infos(&file_path) {
+        Ok(infos) => CommandResult::success(infos),
+        Err(e) => CommandResult::error(format!("读取工作表列表失败: {}", e)),
+    }
+}
+
+/// 获取困难类型选项
+#[tauri::command]
+pub async fn get_difficulty_type_options() -> CommandResult<Vec<DifficultyTypeOption>> {
+    let options = DifficultyType::all()
+        .into_iter()
+        .map(DifficultyTypeOption::from)
+        .collect();
+
+    CommandResult::success(options)
+}
+
+/// 根据文件名关键字推断困难类型, 用于选择文件后预选下拉框; 无法唯一确定时返回 None
+#[tauri::command]
+pub async fn guess_difficulty_type_from_name(
+    file_name: String,
+) -> CommandResult<Option<DifficultyTypeOption>> {
+    let option =
+        xlsx::guess_difficulty_type_from_file_name(&file_name).map(DifficultyTypeOption::from);
+    CommandResult::success(option)
+}
+
+/// 后端版本与构建信息
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppInfo {
+    pub version: String, // 版本号
+    pub target: String,  // 目标平台三元组
+}
+
+/// 获取后端版本与构建信息, 便于排查问题时确认用户的具体版本
+#[tauri::command]
+pub async fn get_app_info() -> CommandResult<AppInfo> {
+    CommandResult::success(AppInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        target: env!("TARGET").to_string(),
+    })
+}
+
+/// 格式化字节数 (1024 进制, 与前端 formatFileSize 一致)
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["Bytes", "KB", "MB", "GB", "TB", "PB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let formatted = format!("{:.2}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    format!("{} {}", formatted, UNITS[unit])
+}
+
+/// 文件信息结构
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub extension: String,
+    pub data_row_count: Option<usize>, // 第一个工作表的数据行数
+    pub encoding: Option<String>,      // 分隔符文本文件识别出的编码, Excel 文件为空
+}
+
+/// 困难类型选项
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DifficultyTypeOption {
+    pub label: String,
+    pub value: String,
+    pub code: String, // 稳定代码, 见 DifficultyType::code
+}
+
+impl From<DifficultyType> for DifficultyTypeOption {
+    fn from(difficulty_type: DifficultyType) -> Self {
+        Self {
+            label: difficulty_type.to_string(),
+            value: difficulty_type.to_string(),
+            code: difficulty_type.code().to_string(),
+        }
+    }
+}
+
+/// 导出匹配结果到 Excel 文件
+#[tauri::command]
+#[allow(clippy::too_many_arguments)] // 参数与前端的可选导出项一一对应
+pub async fn export_matches_to_excel(
+    matches: Vec<MatchResult>,
+    output_path: String,
+    group_by_type: Option<bool>,
+    append_timestamp: Option<bool>,
+    show_raw_id: Option<bool>,
+    show_demographics: Option<bool>,
+    mask_ids: Option<bool>,
+    theme: Option<ExportTheme>,
+    sort_by: Option<Vec<SortKey>>,
+    incomplete_students: Option<Vec<Student>>,
+) -> CommandResult<String> {
+    // 前端可能合并了多次匹配的结果, 同一 (学生, 困难类型) 只导出一条
+    let matches = dedup_match_results(matches);
+    let output_path = if append_timestamp.unwrap_or(false) {
+        timestamped_path(&output_path, Local::now().naive_local())
+    } else {
+        output_path
+    };
+
+    let options = ReportOptions {
+        group_by_type: group_by_type.unwrap_or(false),
+        show_raw_id: show_raw_id.unwrap_or(false),
+        show_demographics: show_demographics.unwrap_or(false),
+        mask_ids: mask_ids.unwrap_or(false),
+        theme: theme.unwrap_or_default(),
+        sort_by: sort_by.unwrap_or_default(),
+        incomplete_students: incomplete_students.unwrap_or_default(),
+    };
+
+    let result = create_excel_report(&matches, &output_path, &options)
+        .map(|_| {
+            tracing::info!(output_path, match_count = matches.len(), "导出 Excel 完成");
+            output_path
+        })
+        .map_err(|e| format!("导出 Excel 失败: {}", e));
+    CommandResult::from_result(result)
+}
+
+/// 仅导出统计信息 (学生总数、匹配数、匹配率及各困难类型数量) 到 Excel 文件
+#[tauri::command]
+pub async fn export_statistics_to_excel(
+    stats: MatchStatistics,
+    output_path: String,
+) -> CommandResult<String> {
+    let result = create_statistics_report(&stats, &output_path)
+        .map(|_| {
+            tracing::info!(output_path, "导出统计信息完成");
+            output_path
+        })
+        .map_err(|e| format!("导出统计信息失败: {}", e));
+    CommandResult::from_result(result)
+}
+
+/// 检查输出路径是否可写, 可在耗时的匹配开始前调用
+#[tauri::command]
+pub async fn check_output_writable(path: String) -> CommandResult<()> {
+    CommandResult::from_result(ensure_output_writable(Path::new(&path)))
+}
+
+/// 写入检查临时文件的序号, 避免并发检查同一目录时文件名冲突
+static WRITE_PROBE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// 确认输出文件所在目录存在且可写 (实际创建并删除一个临时文件)
+fn ensure_output_writable(path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        return Err(format!("输出路径是一个文件夹: {}", path.display()));
+    }
+
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if !dir.is_dir() {
+        return Err(format!("输出目录不存在: {}", dir.display()));
+    }
+
+    let probe = dir.join(format!(
+        ".tauri-app-write-check-{}-{}",
+        std::process::id(),
+        WRITE_PROBE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("输出目录不可写 ({}): {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+
+    // 已存在的目标文件可能是只读的, 或正被 Excel 占用
+    if path.exists() {
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("无法覆盖已有文件 ({}): {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// 在文件扩展名前插入 `_YYYYMMDD_HHMMSS` 时间戳
+fn timestamped_path(path: &str, time: NaiveDateTime) -> String {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let mut file_name = format!("{}_{}", stem, time.format("%Y%m%d_%H%M%S"));
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        file_name.push('.');
+        file_name.push_str(ext);
+    }
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// 学校为空时使用的分组名
+const UNASSIGNED_SCHOOL: &str = "未分配学校";
+
+/// 将学校名称转换为合法的文件名: 替换路径分隔符等 Windows 不允许的字符, 去除首尾空白和句点
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_matches('.');
+    if sanitized.is_empty() {
+        "_".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// 按学校拆分匹配结果, 每个学校写入 `output_dir` 下的一个 Excel 文件, 返回写入的文件路径
+///
+/// 学校按首次出现的顺序输出; 清理后重名的文件追加 `_2`、`_3` 等序号。
+fn write_reports_by_school(
+    matches: &[MatchResult],
+    output_dir: &Path,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut groups: Vec<(&str, Vec<MatchResult>)> = Vec::new();
+    for match_result in matches {
+        let school = match_result
+            .student
+            .school
+            .as_deref()
+            .map(str::trim)
+            .filter(|school| !school.is_empty())
+            .unwrap_or(UNASSIGNED_SCHOOL);
+        match groups.iter_mut().find(|(name, _)| *name == school) {
+            Some((_, group)) => group.push(match_result.clone()),
+            None => groups.push((school, vec![match_result.clone()])),
+        }
+    }
+
+    let mut used_names = HashSet::new();
+    let mut paths = Vec::new();
+    for (school, group) in groups {
+        let base_name = sanitize_file_name(school);
+        let file_name = (1..)
+            .map(|n| match n {
+                1 => base_name.clone(),
+                n => format!("{}_{}", base_name, n),
+            })
+            .find(|name| used_names.insert(name.to_lowercase()))
+            .unwrap_or_default();
+
+        let path = output_dir
+            .join(format!("{}.xlsx", file_name))
+            .to_string_lossy()
+            .into_owned();
+        create_excel_report(&group, &path, &ReportOptions::default())?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// 按学校分别导出匹配结果, 每个学校一个 Excel 文件, 学校为空的记录归入 "未分配学校"
+#[tauri::command]
+pub async fn export_matches_by_school(
+    matches: Vec<MatchResult>,
+    output_dir: String,
+) -> CommandResult<Vec<String>> {
+    let matches = dedup_match_results(matches);
+    let result = write_reports_by_school(&matches, Path::new(&output_dir))
+        .inspect(|paths| {
+            tracing::info!(
+                output_dir,
+                match_count = matches.len(),
+                file_count = paths.len(),
+                "按学校导出 Excel 完成"
+            );
+        })
+        .map_err(|e| format!("按学校导出失败: {}", e));
+    CommandResult::from_result(result)
+}
+
+/// 在后端重新执行匹配并直接导出 Excel 报告, 避免通过 IPC 传输完整的匹配列表
+#[tauri::command]
+pub async fn export_matches_from_files(
+    app: AppHandle,
+    student_file_path: String,
+    difficulty_file_path: String,
+    difficulty_type: String,
+    output_path: String,
+    student_column_config: Option<StudentColumnConfig>,
+) -> CommandResult<String> {
+    let Some(difficulty_enum) = DifficultyType::from_label(&difficulty_type) else {
+        return CommandResult::error(format!("未知的困难类型: {}", difficulty_type));
+    };
+
+    let run = match run_difficulty_match(
+        &app,
+        None,
+        &student_file_path,
+        &difficulty_file_path,
+        difficulty_enum,
+        &student_column_config.unwrap_or_default(),
+        &MatchOptions::default(),
+        &|_| {},
+    ) {
+        Ok(run) => run,
+        Err(e) => return CommandResult::error(e),
+    };
+    let matches = run.matches;
+
+    // 资料不全的学生未参与匹配, 在报告中单独列出
+    let options = ReportOptions {
+        incomplete_students: run.incomplete_students,
+        ..Default::default()
+    };
+    match create_excel_report(&matches, &output_path, &options) {
+        Ok(_) => {
+            tracing::info!(output_path, match_count = matches.len(), "导出 Excel 完成");
+            CommandResult::success(output_path)
+        }
+        Err(e) => CommandResult::error(format!("导出 Excel 失败: {}", e)),
+    }
+}
+
+/// 列配置方案的保存文件名 (位于应用配置目录)
+const MAPPING_PROFILES_FILE: &str = "mapping_profiles.json";
+
+/// 应用配置目录下的列配置方案文件路径
+fn mapping_profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(MAPPING_PROFILES_FILE))
+        .map_err(|e| format!("无法获取应用配置目录: {}", e))
+}
+
+/// 读取已保存的列配置方案, 文件不存在时返回空列表
+fn read_mapping_profiles(path: &Path) -> Result<Vec<(String, StudentColumnConfig)>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("无法读取列配置方案: {}", e)),
+    };
+    serde_json::from_str(&content).map_err(|e| format!("列配置方案文件格式错误: {}", e))
+}
+
+/// 保存列配置方案, 同名方案覆盖原有配置
+fn write_mapping_profile(
+    path: &Path,
+    name: &str,
+    config: StudentColumnConfig,
+) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("方案名称不能为空".to_string());
+    }
+
+    let mut profiles = read_mapping_profiles(path)?;
+    match profiles.iter_mut().find(|(existing, _)| existing == name) {
+        Some((_, existing)) => *existing = config,
+        None => profiles.push((name.to_string(), config)),
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("无法创建配置目录: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&profiles)
+        .map_err(|e| format!("无法序列化列配置方案: {}", e))?;
+    std::fs::write(path, content).map_err(|e| format!("无法保存列配置方案: {}", e))
+}
+
+/// 保存命名的学生信息表列配置方案
+#[tauri::command]
+pub async fn save_mapping_profile(
+    app: AppHandle,
+    name: String,
+    config: StudentColumnConfig,
+) -> CommandResult<()> {
+    let result =
+        mapping_profiles_path(&app).and_then(|path| write_mapping_profile(&path, &name, config));
+    CommandResult::from_result(result)
+}
+
+/// 读取所有已保存的列配置方案 (按保存顺序)
+#[tauri::command]
+pub async fn load_mapping_profiles(
+    app: AppHandle,
+) -> CommandResult<Vec<(String, StudentColumnConfig)>> {
+    let result = mapping_profiles_path(&app).and_then(|path| read_mapping_profiles(&path));
+    CommandResult::from_result(result)
+}
+
+/// 使用系统默认程序打开文件 (如导出后的报告)
+#[tauri::command]
+pub async fn open_path_in_default_app(path: String) -> CommandResult<()> {
+    if !Path::new(&path).exists() {
+        return CommandResult::error(format!("文件不存在: {}", path));
+    }
+
+    match tauri_plugin_opener::open_path(&path, None::<&str>) {
+        Ok(_) => CommandResult::success(()),
+        Err(e) => CommandResult::error(format!("打开文件失败: {}", e)),
+    }
+}
+
+/// 生成匿名化样例文件, 便于用户在反馈问题时附上不含真实信息的表格
+#[tauri::command]
+pub async fn anonymize_file(
+    input_path: String,
+    output_path: String,
+    sheet_index: usize,
+) -> CommandResult<String> {
+    match anonymize_sheet(&input_path, &output_path, sheet_index) {
+        Ok(_) => {
+            tracing::info!(input_path, output_path, sheet_index, "生成匿名样例完成");
+            CommandResult::success(output_path)
+        }
+        Err(e) => CommandResult::error(format!("生成匿名样例失败: {}", e)),
+    }
+}
+
+/// 导出报告的列标题
+const REPORT_HEADERS: [&str; 8] = [
+    "序号",
+    "学生姓名",
+    "身份证号",
+    "学号",
+    "班级",
+    "年级",
+    "学校",
+    "困难类型",
+];
+
+/// 导出匹配结果到 CSV 文件
+#[tauri::command]
+pub async fn export_matches_to_csv(
+    matches: Vec<MatchResult>,
+    output_path: String,
+    mask_ids: Option<bool>,
+) -> CommandResult<String> {
+    let matches = dedup_match_results(matches);
+    let matches = if mask_ids.unwrap_or(false) {
+        mask_match_ids(&matches)
+    } else {
+        matches
+    };
+
+    match create_csv_report(&matches, &output_path) {
+        Ok(_) => {
+            tracing::info!(output_path, match_count = matches.len(), "导出 CSV 完成");
+            CommandResult::success(output_path)
+        }
+        Err(e) => CommandResult::error(format!("导出 CSV 失败: {}", e)),
+    }
+}
+
+/// 导出匹配结果到 JSON 文件 (UTF-8)
+#[tauri::command]
+pub async fn export_matches_to_json(
+    matches: Vec<MatchResult>,
+    output_path: String,
+    pretty: bool,
+    mask_ids: Option<bool>,
+) -> CommandResult<String> {
+    let matches = dedup_match_results(matches);
+    let matches = if mask_ids.unwrap_or(false) {
+        mask_match_ids(&matches)
+    } else {
+        matches
+    };
+
+    let result = write_json_report(&matches, &output_path, pretty)
+        .map(|_| {
+            tracing::info!(output_path, match_count = matches.len(), "导出 JSON 完成");
+            output_path
+        })
+        .map_err(|e| format!("导出 JSON 失败: {}", e));
+    CommandResult::from_result(result)
+}
+
+/// 一次导出多种格式时可选的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Xlsx,
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// 对应的文件扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Xlsx => "xlsx",
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// 按给定格式依次写出 `{output_base}.{扩展名}`, 重复的格式只写一次
+///
+/// 某个格式失败时立即停止, 错误信息中列出失败的格式及已成功写出的文件。
+fn write_multi_format_reports(
+    matches: &[MatchResult],
+    output_base: &str,
+    formats: &[ExportFormat],
+) -> Result<Vec<String>, String> {
+    let mut written: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    for &format in formats {
+        if !seen.insert(format) {
+            continue;
+        }
+        let path = format!("{}.{}", output_base, format.extension());
+        let result = match format {
+            ExportFormat::Xlsx => create_excel_report(matches, &path, &ReportOptions::default())
+                .map_err(|e| e.to_string()),
+            ExportFormat::Csv => create_csv_report(matches, &path).map_err(|e| e.to_string()),
+            ExportFormat::Json => {
+                write_json_report(matches, &path, true).map_err(|e| e.to_string())
+            }
+        };
+        if let Err(e) = result {
+            let succeeded = if written.is_empty() {
+                "无".to_string()
+            } else {
+                written.join(", ")
+            };
+            return Err(format!(
+                "导出 {} 失败: {}; 已成功导出: {}",
+                format.extension().to_uppercase(),
+                e,
+                succeeded
+            ));
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// 一次导出多种格式 (Excel / CSV / JSON), 返回所有写出的文件路径
+#[tauri::command]
+pub async fn export_matches_multi(
+    matches: Vec<MatchResult>,
+    output_base: String,
+    formats: Vec<ExportFormat>,
+) -> CommandResult<Vec<String>> {
+    if formats.is_empty() {
+        return CommandResult::error("未选择导出格式".to_string());
+    }
+    let matches = dedup_match_results(matches);
+    let result = write_multi_format_reports(&matches, &output_base, &formats).inspect(|paths| {
+        tracing::info!(
+            output_base,
+            match_count = matches.len(),
+            file_count = paths.len(),
+            "多格式导出完成"
+        );
+    });
+    CommandResult::from_result(result)
+}
+
+/// 将匹配结果序列化为 JSON 并写入文件
+fn write_json_report(
+    matches: &[MatchResult],
+    output_path: &str,
+    pretty: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if pretty {
+        serde_json::to_string_pretty(matches)?
+    } else {
+        serde_json::to_string(matches)?
+    };
+    std::fs::write(output_path, content)?;
+    Ok(())
+}
+
+/// 创建 CSV 报告 (UTF-8 带 BOM, 以便 Excel 正确识别中文)
+fn create_csv_report(matches: &[MatchResult], output_path: &str) -> std::io::Result<()> {
+    let mut content = String::from("\u{FEFF}");
+    content.push_str(&REPORT_HEADERS.join(","));
+    content.push_str("\r\n");
+
+    for (index, match_result) in matches.iter().enumerate() {
+        let student = &match_result.student;
+        let fields = [
+            (index + 1).to_string(),
+            student.name.clone(),
+            student.id_number.clone(),
+            student.student_id.clone().unwrap_or_default(),
+            student.class.clone().unwrap_or_default(),
+            student.grade.clone().unwrap_or_default(),
+            student.school.clone().unwrap_or_default(),
+            match_result.difficult_info.difficulty_type.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| escape_csv_field(f)).collect();
+        content.push_str(&line.join(","));
+        content.push_str("\r\n");
+    }
+
+    std::fs::write(output_path, content)
+}
+
+/// 转义 CSV 字段: 含逗号、引号或换行时加引号, 内部引号加倍
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Excel 工作表名称的最大长度
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// 需人工复核行的底纹颜色
+const FLAGGED_ROW_COLOR: &str = "#FFEB3B";
+
+/// 明细表自动列宽的上下限 (单位为字符宽度)
+const MIN_COLUMN_WIDTH: usize = 6;
+const MAX_COLUMN_WIDTH: usize = 50;
+
+/// 文本在 Excel 中的显示宽度, 中日韩文字及全角字符按 2 个字符计
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// 按列中最长内容计算列宽, 两侧各留一个字符的边距
+fn autofit_width(max_display_width: usize) -> f64 {
+    (max_display_width + 2).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH) as f64
+}
+
+/// Excel 报告的配色, 颜色为 `#RRGGBB` 格式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportTheme {
+    pub header_bg: String, // 标题行背景色
+    pub header_fg: String, // 标题行文字颜色
+}
+
+impl Default for ExportTheme {
+    fn default() -> Self {
+        Self {
+            header_bg: "#4CAF50".to_string(),
+            header_fg: "#FFFFFF".to_string(),
+        }
+    }
+}
+
+impl ExportTheme {
+    /// 逐项校验颜色, 无效的颜色回退为默认值
+    fn validated(&self) -> Self {
+        let default = Self::default();
+        let pick = |color: &str, fallback: String| {
+            if is_hex_color(color) {
+                color.to_string()
+            } else {
+                tracing::warn!(color, "无效的颜色, 使用默认值");
+                fallback
+            }
+        };
+        Self {
+            header_bg: pick(&self.header_bg, default.header_bg),
+            header_fg: pick(&self.header_fg, default.header_fg),
+        }
+    }
+}
+
+/// 判断是否为 `#RRGGBB` 格式的颜色
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// 报告所用的单元格格式
+struct ReportFormats {
+    header: Format,
+    data: Format,
+    number: Format,
+    // 非身份证号精确匹配的行使用黄色底纹
+    flagged_data: Format,
+    flagged_number: Format,
+    total: Format,   // 合计行
+    percent: Format, // 百分比数值
+    count: Format,   // 统计数量, 带千位分隔符
+}
+
+impl ReportFormats {
+    fn new(theme: &ExportTheme) -> Self {
+        let theme = theme.validated();
+        Self {
+            // 设置标题格式
+            header: Format::new()
+                .set_bold()
+                .set_background_color(theme.header_bg.as_str())
+                .set_font_color(theme.header_fg.as_str())
+                .set_align(rust_xlsxwriter::FormatAlign::Center),
+            // 设置数据格式
+            data: Format::new().set_align(rust_xlsxwriter::FormatAlign::Left),
+            number: Format::new().set_align(rust_xlsxwriter::FormatAlign::Center),
+            flagged_data: Format::new()
+                .set_align(rust_xlsxwriter::FormatAlign::Left)
+                .set_background_color(FLAGGED_ROW_COLOR),
+            flagged_number: Format::new()
+                .set_align(rust_xlsxwriter::FormatAlign::Center)
+                .set_background_color(FLAGGED_ROW_COLOR),
+            total: Format::new()
+                .set_bold()
+                .set_align(rust_xlsxwriter::FormatAlign::Left)
+                .set_border_top(rust_xlsxwriter::FormatBorder::Thin),
+            percent: Format::new()
+                .set_align(rust_xlsxwriter::FormatAlign::Center)
+                .set_num_format("0.00%"),
+            count: Format::new()
+                .set_align(rust_xlsxwriter::FormatAlign::Center)
+                .set_num_format("#,##0"),
+        }
+    }
+}
+
+/// Excel 报告选项
+#[derive(Debug, Clone, Default)]
+struct ReportOptions {
+    group_by_type: bool,               // 按困难类型分工作表
+    show_raw_id: bool,                 // 额外输出困难名册中的原始身份证号
+    show_demographics: bool,           // 额外输出由身份证号解析的年龄和性别
+    mask_ids: bool,                    // 遮挡身份证号中间 8 位
+    theme: ExportTheme,                // 标题行配色
+    sort_by: Vec<SortKey>,             // 明细排序字段, 为空时保持读取顺序
+    incomplete_students: Vec<Student>, // 资料不全的学生, 非空时单独输出 "资料不全" 工作表
+}
+
+/// 明细表中可选的附加列 (位于固定列之后)
+#[derive(Debug, Clone, Copy)]
+enum ExtraColumn {
+    RawId,
+    Age,
+    Gender,
+}
+
+impl ExtraColumn {
+    fn header(self) -> &'static str {
+        match self {
+            Self::RawId => "原始身份证号",
+            Self::Age => "年龄",
+            Self::Gender => "性别",
+        }
+    }
+}
+
+impl ReportOptions {
+    fn extra_columns(&self) -> Vec<ExtraColumn> {
+        let mut columns = Vec::new();
+        if self.show_raw_id {
+            columns.push(ExtraColumn::RawId);
+        }
+        if self.show_demographics {
+            columns.extend([ExtraColumn::Age, ExtraColumn::Gender]);
+        }
+        columns
+    }
+}
+
+/// 创建 Excel 报告
+///
+/// `group_by_type` 为 true 时, 每个出现的困难类型单独生成一个工作表。
+fn create_excel_report(
+    matches: &[MatchResult],
+    output_path: &str,
+    options: &ReportOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut workbook = Workbook::new();
+    let formats = ReportFormats::new(&options.theme);
+
+    let mut sorted;
+    let matches = if options.sort_by.is_empty() {
+        matches
+    } else {
+        sorted = matches.to_vec();
+        sort_match_results(&mut sorted, &options.sort_by);
+        &sorted
+    };
+
+    if options.group_by_type {
+        for difficulty_type in DifficultyType::all() {
+            let group: Vec<&MatchResult> = matches
+                .iter()
+                .filter(|m| m.difficult_info.difficulty_type == difficulty_type)
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            let sheet_name: String = difficulty_type
+                .to_string()
+                .chars()
+                .take(MAX_SHEET_NAME_LEN)
+                .collect();
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(sheet_name)?;
+            write_detail_sheet(worksheet, &group, &formats, options)?;
+        }
+    } else {
+        let all: Vec<&MatchResult> = matches.iter().collect();
+        write_detail_sheet(workbook.add_worksheet(), &all, &formats, options)?;
+    }
+
+    if !options.incomplete_students.is_empty() {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(INCOMPLETE_SHEET_NAME)?;
+        write_incomplete_sheet(worksheet, &options.incomplete_students, &formats)?;
+    }
+
+    // 添加统计信息工作表
+    let mut difficulty_counts = HashMap::new();
+    for match_result in matches {
+        *difficulty_counts
+            .entry(match_result.difficult_info.difficulty_type)
+            .or_insert(0) += 1;
+    }
+    let mut totals = vec![("总匹配数量", matches.len() as f64, &formats.count)];
+    if !options.incomplete_students.is_empty() {
+        totals.push((
+            "资料不全人数",
+            options.incomplete_students.len() as f64,
+            &formats.count,
+        ));
+    }
+    write_statistics_sheet(
+        workbook.add_worksheet(),
+        &formats,
+        &totals,
+        &difficulty_counts,
+    )?;
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+/// 资料不全工作表的名称
+const INCOMPLETE_SHEET_NAME: &str = "资料不全";
+
+/// 资料不全工作表的表头 (没有身份证号, 只列出可用于核对的信息)
+const INCOMPLETE_HEADERS: [&str; 6] = ["序号", "姓名", "学号", "班级", "年级", "学校"];
+
+/// 写入资料不全的学生 (缺少身份证号, 未参与匹配), 供人工补充后重新匹配
+fn write_incomplete_sheet(
+    worksheet: &mut Worksheet,
+    students: &[Student],
+    formats: &ReportFormats,
+) -> Result<(), XlsxError> {
+    let mut widths = INCOMPLETE_HEADERS.map(display_width);
+    for (col, header) in INCOMPLETE_HEADERS.iter().enumerate() {
+        worksheet.write_with_format(0, col as u16, *header, &formats.header)?;
+    }
+
+    for (index, student) in students.iter().enumerate() {
+        let row = index as u32 + 1;
+        worksheet.write_with_format(row, 0, row, &formats.number)?;
+        let fields = [
+            student.name.as_str(),
+            student.student_id.as_deref().unwrap_or_default(),
+            student.class.as_deref().unwrap_or_default(),
+            student.grade.as_deref().unwrap_or_default(),
+            student.school.as_deref().unwrap_or_default(),
+        ];
+        for (col, field) in (1..).zip(fields) {
+            worksheet.write_with_format(row, col as u16, field, &formats.data)?;
+            widths[col] = widths[col].max(display_width(field));
+        }
+    }
+
+    for (col, width) in widths.iter().enumerate() {
+        worksheet.set_column_width(col as u16, autofit_width(*width))?;
+    }
+    Ok(())
+}
+
+/// 写入统计信息工作表: 先写汇总项, 再按困难类型顺序写出全部类型的数量 (未出现的记为 0)
+fn write_statistics_sheet(
+    worksheet: &mut Worksheet,
+    formats: &ReportFormats,
+    totals: &[(&str, f64, &Format)],
+    difficulty_counts: &HashMap<DifficultyType, usize>,
+) -> Result<(), XlsxError> {
+    worksheet.set_name("统计信息")?;
+
+    // 写入统计信息标题
+    worksheet.write_with_format(0, 0, "统计项目", &formats.header)?;
+    worksheet.write_with_format(0, 1, "数量", &formats.header)?;
+
+    let mut row = 1;
+    for (label, value, format) in totals {
+        worksheet.write_with_format(row, 0, *label, &formats.data)?;
+        worksheet.write_with_format(row, 1, *value, format)?;
+        row += 1;
+    }
+
+    worksheet.write_with_format(row, 0, "按困难类型分布:", &formats.data)?;
+    row += 1;
+
+    for difficulty_type in DifficultyType::all() {
+        let count = difficulty_counts
+            .get(&difficulty_type)
+            .copied()
+            .unwrap_or(0);
+        worksheet.write_with_format(row, 0, difficulty_type.to_string(), &formats.data)?;
+        worksheet.write_with_format(row, 1, count as u32, &formats.count)?;
+        row += 1;
+    }
+
+    // 设置统计表列宽
+    worksheet.set_column_width(0, 25.0)?;
+    worksheet.set_column_width(1, 10.0)?;
+    Ok(())
+}
+
+/// 创建仅包含统计信息的 Excel 报告
+fn create_statistics_report(
+    stats: &MatchStatistics,
+    output_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut workbook = Workbook::new();
+    let formats = ReportFormats::new(&ExportTheme::default());
+
+    let mut totals = vec![
+        ("学生总数", stats.total_students as f64, &formats.count),
+        ("总匹配数量", stats.total_matches as f64, &formats.count),
+        ("匹配率", stats.match_rate, &formats.percent),
+    ];
+    if stats.incomplete_students > 0 {
+        totals.push((
+            "资料不全人数",
+            stats.incomplete_students as f64,
+            &formats.count,
+        ));
+    }
+    write_statistics_sheet(
+        workbook.add_worksheet(),
+        &formats,
+        &totals,
+        &stats.difficulty_type_counts,
+    )?;
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+/// 写入匹配明细工作表
+fn write_detail_sheet(
+    worksheet: &mut Worksheet,
+    matches: &[&MatchResult],
+    formats: &ReportFormats,
+    options: &ReportOptions,
+) -> Result<(), XlsxError> {
+    let header_format = &formats.header;
+    let extra_columns = options.extra_columns();
+    let first_extra_col = REPORT_HEADERS.len() as u16;
+    let last_col = first_extra_col + extra_columns.len() as u16 - 1;
+
+    // 各列内容的最大显示宽度, 用于自动调整列宽
+    let mut widths = vec![0; last_col as usize + 1];
+    let mut measure = |col: u16, text: &str| {
+        let width = &mut widths[col as usize];
+        *width = (*width).max(display_width(text));
+    };
+
+    // 写入标题行
+    for (col, header) in REPORT_HEADERS.iter().enumerate() {
+        worksheet.write_with_format(0, col as u16, *header, header_format)?;
+        measure(col as u16, header);
+    }
+    for (col, column) in (first_extra_col..).zip(&extra_columns) {
+        worksheet.write_with_format(0, col, column.header(), header_format)?;
+        measure(col, column.header());
+    }
+
+    let display_id = |id: &str| {
+        if options.mask_ids {
+            mask_id_number(id)
+        } else {
+            id.to_string()
+        }
+    };
+
+    // 写入数据
+    for (row, match_result) in matches.iter().enumerate() {
+        let row = row + 1; // 跳过标题行
+
+        let (data_format, number_format) = if match_result.match_kind == MatchKind::ExactId {
+            (&formats.data, &formats.number)
+        } else {
+            (&formats.flagged_data, &formats.flagged_number)
+        };
+
+        worksheet.write_with_format(row as u32, 0, row as u32, number_format)?;
+        measure(0, &row.to_string());
+
+        let student = &match_result.student;
+        let fields = [
+            student.name.clone(),
+            display_id(&student.id_number),
+            student.student_id.clone().unwrap_or_default(),
+            student.class.clone().unwrap_or_default(),
+            student.grade.clone().unwrap_or_default(),
+            student.school.clone().unwrap_or_default(),
+            match_result.difficult_info.difficulty_type.to_string(),
+        ];
+        for (col, field) in (1..).zip(&fields) {
+            worksheet.write_with_format(row as u32, col, field, data_format)?;
+            measure(col, field);
+        }
+
+        // 年龄和性别按完整身份证号解析, 遮挡只影响输出
+        let demographics = options
+            .show_demographics
+            .then(|| parse_id_demographics(&match_result.student.id_number))
+            .flatten();
+        for (col, column) in (first_extra_col..).zip(&extra_columns) {
+            match (column, &demographics) {
+                (ExtraColumn::RawId, _) => {
+                    let raw_id = display_id(&match_result.difficult_info.raw_id_number);
+                    measure(col, &raw_id);
+                    worksheet.write_with_format(row as u32, col, raw_id, data_format)?
+                }
+                (ExtraColumn::Age, Some(demographics)) => {
+                    measure(col, &demographics.age.to_string());
+                    worksheet.write_with_format(row as u32, col, demographics.age, number_format)?
+                }
+                (ExtraColumn::Gender, Some(demographics)) => {
+                    let gender = demographics.gender.to_string();
+                    measure(col, &gender);
+                    worksheet.write_with_format(row as u32, col, gender, data_format)?
+                }
+                (_, None) => worksheet.write_with_format(row as u32, col, "", data_format)?,
+            };
+        }
+    }
+
+    // 数据下方的合计行, 不在筛选范围内
+    let total_row = matches.len() as u32 + 1;
+    worksheet.write_with_format(total_row, 1, "合计", &formats.total)?;
+    worksheet.write_with_format(total_row, 2, matches.len() as u32, &formats.total)?;
+
+    // 存在需复核的行时, 在合计行下方添加颜色说明
+    if matches.iter().any(|m| m.match_kind != MatchKind::ExactId) {
+        worksheet.write_with_format(
+            total_row + 2,
+            1,
+            "黄色底纹: 按姓名或遮挡身份证号匹配, 请人工复核",
+            &formats.flagged_data,
+        )?;
+    }
+
+    // 冻结标题行, 并为标题与数据区域添加筛选
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofilter(0, 0, matches.len() as u32, last_col)?;
+
+    // 按内容设置列宽 (合计行和颜色说明不参与计算)
+    for (col, width) in widths.iter().enumerate() {
+        worksheet.set_column_width(col as u16, autofit_width(*width))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{DataType, Reader, Xlsx, open_workbook};
+    use chrono::NaiveDate;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("tauri-app-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn sample_match(name: &str, id_number: &str, difficulty_type: DifficultyType) -> MatchResult {
+        MatchResult {
+            student: Student {
+                name: name.to_string(),
+                raw_name: name.to_string(),
+                id_number: id_number.to_string(),
+                student_id: None,
+                class: Some("1班".to_string()),
+                grade: Some("一年级".to_string()),
+                school: Some("第一小学".to_string()),
+                incomplete: false,
+            },
+            difficult_info: DifficultPerson {
+                id_number: id_number.to_string(),
+                raw_id_number: id_number.to_string(),
+                difficulty_type,
+                name: None,
+            },
+            match_kind: MatchKind::ExactId,
+        }
+    }
+
+    #[test]
+    fn test_create_excel_report_group_by_type() {
+        let path = temp_path("grouped-report.xlsx");
+        let matches = vec![
+            sample_match(
+                "张三",
+                "11010120100307002X",
+                DifficultyType::UrbanMinimumLiving,
+            ),
+            sample_match(
+                "李四",
+                "110101201205060018",
+                DifficultyType::LowIncomePopulation,
+            ),
+            sample_match(
+                "王五",
+                "110101201001010011",
+                DifficultyType::UrbanMinimumLiving,
+            ),
+        ];
+
+        let options = ReportOptions {
+            group_by_type: true,
+            show_raw_id: true,
+            show_demographics: true,
+            mask_ids: true,
+            ..Default::default()
+        };
+        create_excel_report(&matches, &path, &options).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(
+            workbook.sheet_names(),
+            ["城镇低保", "低收入人口", "统计信息"]
+        );
+        let range = workbook.worksheet_range("城镇低保").unwrap();
+        assert_eq!(range.height(), 4); // 标题 + 2 条数据 + 合计行
+        assert_eq!(
+            range.get_value((0, 8)).and_then(|v| v.as_string()),
+            Some("原始身份证号".to_string())
+        );
+        assert_eq!(
+            range.get_value((1, 10)).and_then(|v| v.as_string()),
+            Some("女".to_string())
+        );
+        assert_eq!(
+            range.get_value((1, 2)).and_then(|v| v.as_string()),
+            Some("110101********002X".to_string())
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_create_excel_report_incomplete_students() {
+        let path = temp_path("incomplete-report.xlsx");
+        let matches = vec![sample_match(
+            "张三",
+            "11010120100307002X",
+            DifficultyType::UrbanMinimumLiving,
+        )];
+        let mut incomplete = sample_match("李四", "", DifficultyType::UrbanMinimumLiving).student;
+        incomplete.incomplete = true;
+
+        let options = ReportOptions {
+            incomplete_students: vec![incomplete],
+            ..Default::default()
+        };
+        create_excel_report(&matches, &path, &options).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(
+            workbook.sheet_names(),
+            ["Sheet1", INCOMPLETE_SHEET_NAME, "统计信息"]
+        );
+        let range = workbook.worksheet_range(INCOMPLETE_SHEET_NAME).unwrap();
+        assert_eq!(range.height(), 2);
+        assert_eq!(
+            range.get_value((1, 1)).and_then(|v| v.as_string()),
+            Some("李四".to_string())
+        );
+        assert_eq!(
+            range.get_value((1, 5)).and_then(|v| v.as_string()),
+            Some("第一小学".to_string())
+        );
+        let range = workbook.worksheet_range("统计信息").unwrap();
+        assert_eq!(
+            range.get_value((2, 0)).and_then(|v| v.as_string()),
+            Some("资料不全人数".to_string())
+        );
+
+        // 没有资料不全的学生时不输出该工作表
+        create_excel_report(&matches, &path, &ReportOptions::default()).unwrap();
+        let workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(workbook.sheet_names(), ["Sheet1", "统计信息"]);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_match_statistics_match_rate() {
+        let empty = MatchStatistics::new(0, &[]);
+        assert_eq!(empty.match_rate, 0.0);
+        assert_eq!(empty.difficulty_type_counts.len(), 10);
+        assert!(
+            empty
+                .difficulty_type_counts
+                .values()
+                .all(|&count| count == 0)
+        );
+
+        let matches = vec![sample_match(
+            "张三",
+            "11010120100307002X",
+            DifficultyType::UrbanMinimumLiving,
+        )];
+        let statistics = MatchStatistics::new(3, &matches).with_incomplete_students(2);
+        assert_eq!(statistics.total_students, 3);
+        assert_eq!(statistics.incomplete_students, 2);
+        assert_eq!(statistics.total_matches, 1);
+        // 资料不全的学生不计入匹配率的分母
+        assert_eq!(statistics.match_rate, 0.3333);
+        assert_eq!(statistics.difficulty_type_counts.len(), 10);
+        assert_eq!(
+            statistics.difficulty_type_counts[&DifficultyType::UrbanMinimumLiving],
+            1
+        );
+        assert_eq!(
+            statistics.difficulty_type_counts[&DifficultyType::LowIncomePopulation],
+            0
+        );
+        assert_eq!(statistics.by_grade["一年级"], 1);
+        assert_eq!(statistics.by_school["第一小学"], 1);
+
+        let mut unknown = matches[0].clone();
+        unknown.student.school = None;
+        let statistics = MatchStatistics::new(3, &[unknown]);
+        assert_eq!(statistics.by_school[UNKNOWN_GROUP], 1);
+    }
+
+    #[test]
+    fn test_list_excel_files() {
+        let folder = std::path::PathBuf::from(temp_path("folder"));
+        std::fs::create_dir_all(folder.join("子目录.xlsx")).unwrap();
+        for name in ["b.XLSX", "a.xls", "~$a.xlsx", "说明.txt"] {
+            std::fs::write(folder.join(name), "").unwrap();
+        }
+
+        let files = list_excel_files(&folder).unwrap();
+        let cancel_token = CancellationToken::default();
+        assert_eq!(files, vec![folder.join("a.xls"), folder.join("b.XLSX")]);
+
+        let (status, people) =
+            read_folder_file(&folder.join("a.xls").to_string_lossy(), &cancel_token).unwrap();
+        assert_eq!(status.file_name, "a.xls");
+        assert!(status.error.is_some());
+        assert!(people.is_empty());
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn test_operations_cancel_independently() {
+        let operations = Operations::default();
+        let folder = operations.begin(Some("folder".to_string()));
+        {
+            // 新操作开始时不影响其他操作的取消状态
+            let single = operations.begin(None);
+            assert_eq!(operations.cancel(Some("folder")), 1);
+            assert!(folder.is_cancelled());
+            assert!(!single.is_cancelled());
+
+            assert_eq!(operations.cancel(None), 2);
+            assert!(single.is_cancelled());
+        }
+        // 结束的操作已注销
+        assert_eq!(operations.cancel(None), 1);
+        drop(folder);
+        assert_eq!(operations.cancel(Some("folder")), 0);
+    }
+
+    #[test]
+    fn test_ensure_output_writable() {
+        let dir = std::env::temp_dir();
+        assert!(ensure_output_writable(&dir.join("report.xlsx")).is_ok());
+        assert!(ensure_output_writable(&dir).is_err());
+
+        let missing = dir.join(format!("tauri-app-missing-{}", std::process::id()));
+        let error = ensure_output_writable(&missing.join("report.xlsx")).unwrap_err();
+        assert!(error.starts_with("输出目录不存在"));
+
+        // 并发检查同一目录时临时文件名不能冲突
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let path = dir.join("report.xlsx");
+                std::thread::spawn(move || ensure_output_writable(&path))
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_create_excel_report_totals_row() {
+        let path = temp_path("totals-report.xlsx");
+        let matches = vec![
+            sample_match(
+                "张三",
+                "11010120100307002X",
+                DifficultyType::UrbanMinimumLiving,
+            ),
+            sample_match(
+                "李四",
+                "110101201205060018",
+                DifficultyType::LowIncomePopulation,
+            ),
+        ];
+        create_excel_report(&matches, &path, &ReportOptions::default()).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+        let total_row = matches.len() as u32 + 1;
+        assert_eq!(
+            range.get_value((total_row, 1)).and_then(|v| v.as_string()),
+            Some("合计".to_string())
+        );
+        assert_eq!(
+            range.get_value((total_row, 2)).and_then(|v| v.as_f64()),
+            Some(matches.len() as f64)
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_match_cache_and_paging() {
+        let key = |difficulty_type| MatchCacheKey {
+            student_file: (PathBuf::from("students.xlsx"), SystemTime::UNIX_EPOCH),
+            difficulty_file: (PathBuf::from("difficulty.xlsx"), SystemTime::UNIX_EPOCH),
+            difficulty_type,
+            column_config: StudentColumnConfig::default(),
+            match_options: MatchOptions::default(),
+        };
+        let matches: Vec<MatchResult> = (0..5)
+            .map(|i| {
+                sample_match(
+                    &format!("学生{}", i),
+                    &format!("11010120100307000{}", i),
+                    DifficultyType::UrbanMinimumLiving,
+                )
+            })
+            .collect();
+
+        let cache = MatchCache::default();
+        let runs = std::cell::Cell::new(0);
+        let run = || {
+            runs.set(runs.get() + 1);
+            Ok(matches.clone())
+        };
+        cache
+            .get_or_run(key(DifficultyType::UrbanMinimumLiving), run)
+            .unwrap();
+        let cached = cache
+            .get_or_run(key(DifficultyType::UrbanMinimumLiving), run)
+            .unwrap();
+        assert_eq!(runs.get(), 1);
+        cache
+            .get_or_run(key(DifficultyType::LowIncomePopulation), run)
+            .unwrap();
+        assert_eq!(runs.get(), 2);
+
+        let page = PagedMatches::from_slice(&cached, 3, 10);
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].student.name, "学生3");
+        assert!(PagedMatches::from_slice(&cached, 10, 10).items.is_empty());
+    }
+
+    #[test]
+    fn test_export_theme_validated() {
+        let theme = ExportTheme {
+            header_bg: "#1f4e79".to_string(),
+            header_fg: "white".to_string(),
+        }
+        .validated();
+        assert_eq!(theme.header_bg, "#1f4e79");
+        assert_eq!(theme.header_fg, ExportTheme::default().header_fg);
+
+        assert!(!is_hex_color("4CAF50"));
+        assert!(!is_hex_color("#4CAF5"));
+        assert!(!is_hex_color("#GGGGGG"));
+    }
+
+    #[test]
+    fn test_timestamped_path() {
+        let time = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_opt(9, 8, 7)
+            .unwrap();
+        assert_eq!(
+            timestamped_path("reports/结果.xlsx", time),
+            Path::new("reports")
+                .join("结果_20240305_090807.xlsx")
+                .to_string_lossy()
+        );
+        assert_eq!(timestamped_path("结果", time), "结果_20240305_090807");
+    }
+
+    #[test]
+    fn test_roster_cache() {
+        let path = temp_path("roster-cache.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        let cache = RosterCache::default();
+        let config = StudentColumnConfig::default();
+        let first = cache.get_or_read(&path, &config).unwrap();
+        let second = cache.get_or_read(&path, &config).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // 修改时间变化后重新读取, 并清除旧缓存
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        let third = cache.get_or_read(&path, &config).unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+        assert_eq!(cache.lock().len(), 1);
+        assert_eq!(cache.clear(), 1);
+        assert_eq!(cache.clear(), 0);
+
+        assert!(
+            cache
+                .get_or_read(&temp_path("missing.xlsx"), &config)
+                .is_err()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_command_result_from_result() {
+        let ok = CommandResult::from_result(Ok::<_, ExcelError>(1));
+        assert!(ok.success);
+        assert_eq!(ok.data, Some(1));
+
+        let err =
+            CommandResult::<()>::from_result(Err(ExcelError::FileNotFound("a.xlsx".to_string())));
+        assert!(!err.success);
+        assert_eq!(
+            err.error,
+            Some(ExcelError::FileNotFound("a.xlsx".to_string()).to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 Bytes");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(MAX_UPLOAD_SIZE_BYTES), "50 MB");
+        assert_eq!(format_bytes(3 * 1024_u64.pow(5)), "3 PB");
+    }
+
+    #[test]
+    fn test_inspect_uploaded_file() {
+        let path = temp_path("inspect-upload.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        workbook.save(&path).unwrap();
+
+        let error = inspect_uploaded_file(path.clone(), MAX_UPLOAD_SIZE_BYTES).unwrap_err();
+        assert!(error.contains("没有数据行"));
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        workbook.save(&path).unwrap();
+
+        let file_info = inspect_uploaded_file(path.clone(), MAX_UPLOAD_SIZE_BYTES).unwrap();
+        assert_eq!(file_info.data_row_count, Some(1));
+        assert_eq!(file_info.encoding, None);
+
+        let error = inspect_uploaded_file(path.clone(), 16).unwrap_err();
+        assert!(error.starts_with("文件过大 (超过 16 Bytes)"));
+
+        let _ = std::fs::remove_file(path);
+
+        // 启用宏的工作簿按 xlsx 读取
+        let path = temp_path("inspect-upload.xlsm");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        workbook.save(&path).unwrap();
+
+        let file_info = inspect_uploaded_file(path.clone(), MAX_UPLOAD_SIZE_BYTES).unwrap();
+        assert_eq!(file_info.extension, "xlsm");
+        assert_eq!(file_info.data_row_count, Some(1));
+
+        let _ = std::fs::remove_file(path);
+
+        // 分隔符文本返回识别出的编码
+        let path = temp_path("inspect-upload.csv");
+        std::fs::write(&path, "学生姓名,身份证号\n张三,11010120100307002X\n").unwrap();
+        let file_info = inspect_uploaded_file(path.clone(), MAX_UPLOAD_SIZE_BYTES).unwrap();
+        assert_eq!(file_info.encoding.as_deref(), Some("UTF-8"));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_mapping_profiles() {
+        let path = PathBuf::from(temp_path("profiles")).join(MAPPING_PROFILES_FILE);
+        assert!(read_mapping_profiles(&path).unwrap().is_empty());
+
+        let autumn = StudentColumnConfig {
+            school: 2,
+            ..Default::default()
+        };
+        write_mapping_profile(&path, "2025秋季格式", StudentColumnConfig::default()).unwrap();
+        write_mapping_profile(&path, "旧版格式", StudentColumnConfig::default()).unwrap();
+        write_mapping_profile(&path, " 2025秋季格式 ", autumn.clone()).unwrap();
+        assert!(write_mapping_profile(&path, "  ", autumn.clone()).is_err());
+
+        let profiles = read_mapping_profiles(&path).unwrap();
+        assert_eq!(
+            profiles,
+            vec![
+                ("2025秋季格式".to_string(), autumn),
+                ("旧版格式".to_string(), StudentColumnConfig::default()),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_create_statistics_report() {
+        let path = temp_path("statistics-report.xlsx");
+        let matches = vec![
+            sample_match(
+                "张三",
+                "11010120100307002X",
+                DifficultyType::UrbanMinimumLiving,
+            ),
+            sample_match(
+                "李四",
+                "110101201001010011",
+                DifficultyType::LowIncomePopulation,
+            ),
+        ];
+        let statistics = MatchStatistics::new(8, &matches);
+        create_statistics_report(&statistics, &path).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(workbook.sheet_names(), ["统计信息"]);
+        let range = workbook.worksheet_range("统计信息").unwrap();
+        let cell = |row: u32, col: u32| range.get_value((row, col)).cloned().unwrap();
+        assert_eq!(cell(1, 0).get_string(), Some("学生总数"));
+        assert_eq!(cell(1, 1).get_float(), Some(8.0));
+        assert_eq!(cell(2, 1).get_float(), Some(2.0));
+        assert_eq!(cell(3, 0).get_string(), Some("匹配率"));
+        assert_eq!(cell(3, 1).get_float(), Some(0.25));
+        assert_eq!(cell(4, 0).get_string(), Some("按困难类型分布:"));
+        assert_eq!(cell(5, 1).get_float(), Some(0.0));
+        assert_eq!(cell(9, 0).get_string(), Some("城镇低保"));
+        assert_eq!(cell(9, 1).get_float(), Some(1.0));
+        assert_eq!(cell(14, 0).get_string(), Some("低收入人口"));
+        assert_eq!(range.height(), 15);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_write_reports_by_school() {
+        assert_eq!(sanitize_file_name("第一小学/分校"), "第一小学_分校");
+        assert_eq!(sanitize_file_name(" 实验:中学. "), "实验_中学");
+        assert_eq!(sanitize_file_name(".."), "_");
+
+        let dir = PathBuf::from(temp_path("by-school"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let with_school = |name: &str, id: &str, school: Option<&str>| {
+            let mut match_result = sample_match(name, id, DifficultyType::LowIncomePopulation);
+            match_result.student.school = school.map(str::to_string);
+            match_result
+        };
+        let matches = vec![
+            with_school("张三", "11010120100307002X", Some("第一小学")),
+            with_school("李四", "110101201001010011", None),
+            with_school("王五", "110101201205060018", Some("第一小学")),
+            with_school("赵六", "110101201101010012", Some("第一:小学")),
+            with_school("钱七", "110101201101010013", Some("第一?小学")),
+        ];
+
+        let paths = write_reports_by_school(&matches, &dir).unwrap();
+        let names: Vec<String> = paths
+            .iter()
+            .map(|path| {
+                Path::new(path)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "第一小学.xlsx",
+                "未分配学校.xlsx",
+                "第一_小学.xlsx",
+                "第一_小学_2.xlsx"
+            ]
+        );
+
+        let mut workbook: Xlsx<_> = open_workbook(&paths[0]).unwrap();
+        let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+        // 标题行 + 2 条明细 + 合计行
+        assert_eq!(range.height(), 4);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_autofit_width() {
+        assert_eq!(display_width("序号"), 4);
+        assert_eq!(display_width("11010120100307002X"), 18);
+        assert_eq!(display_width("Ａ校 1班"), 8);
+
+        assert_eq!(autofit_width(0), MIN_COLUMN_WIDTH as f64);
+        assert_eq!(autofit_width(18), 20.0);
+        assert_eq!(autofit_width(200), MAX_COLUMN_WIDTH as f64);
+    }
+
+    #[test]
+    fn test_write_json_report() {
+        let path = temp_path("matches.json");
+        let matches = vec![sample_match(
+            "张三",
+            "11010120100307002X",
+            DifficultyType::UrbanMinimumLiving,
+        )];
+
+        write_json_report(&matches, &path, false).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains('\n'));
+        let parsed: Vec<MatchResult> = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed[0].student.name, "张三");
+        assert_eq!(
+            parsed[0].difficult_info.difficulty_type,
+            DifficultyType::UrbanMinimumLiving
+        );
+
+        write_json_report(&matches, &path, true).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains('\n'));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_write_multi_format_reports() {
+        let dir = PathBuf::from(temp_path("multi-format"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("报告").to_string_lossy().to_string();
+        let matches = vec![sample_match(
+            "张三",
+            "11010120100307002X",
+            DifficultyType::UrbanMinimumLiving,
+        )];
+
+        let paths = write_multi_format_reports(
+            &matches,
+            &base,
+            &[
+                ExportFormat::Xlsx,
+                ExportFormat::Csv,
+                ExportFormat::Json,
+                ExportFormat::Csv,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            paths,
+            [
+                format!("{}.xlsx", base),
+                format!("{}.csv", base),
+                format!("{}.json", base)
+            ]
+        );
+        assert!(paths.iter().all(|path| Path::new(path).is_file()));
+
+        // CSV 路径被目录占用: Excel 已写出, 错误中应列出
+        let failing_base = dir.join("冲突").to_string_lossy().to_string();
+        std::fs::create_dir_all(format!("{}.csv", failing_base)).unwrap();
+        let error = write_multi_format_reports(
+            &matches,
+            &failing_base,
+            &[ExportFormat::Xlsx, ExportFormat::Csv, ExportFormat::Json],
+        )
+        .unwrap_err();
+        assert!(error.starts_with("导出 CSV 失败"));
+        assert!(error.contains(&format!("{}.xlsx", failing_base)));
+        assert!(!Path::new(&format!("{}.json", failing_base)).exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("第一小学"), "第一小学");
+        assert_eq!(escape_csv_field("一年级,1班"), "\"一年级,1班\"");
+        assert_eq!(escape_csv_field("\"实验\"小学"), "\"\"\"实验\"\"小学\"");
+    }
 }
@@ -2,17 +2,71 @@ pub mod command;
 pub mod xlsx;
 
 use command::*;
+use tauri::Manager;
+
+/// 初始化日志, 按天滚动写入应用日志目录
+///
+/// 仅记录文件路径和数量等信息, 不在 info 级别记录身份证号。
+fn init_logging(log_dir: &std::path::Path) {
+    let file_appender = tracing_appender::rolling::daily(log_dir, "tauri-app.log");
+    let _ = tracing_subscriber::fmt()
+        .with_writer(file_appender)
+        .with_ansi(false)
+        .with_max_level(tracing::Level::INFO)
+        .try_init();
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .manage(RosterCache::default())
+        .manage(Operations::default())
+        .manage(MatchCache::default())
+        .setup(|app| {
+            init_logging(&app.path().app_log_dir()?);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             find_students_by_difficulty,
+            find_students_by_difficulty_with_progress,
+            find_students_multi_difficulty,
+            find_students_auto,
+            find_students_by_id_list,
+            summarize_difficulty_files,
+            diff_difficulty_files,
+            get_difficulty_type_layout,
+            selftest_difficulty_file,
+            match_folder,
+            cancel_current_operation,
+            check_output_writable,
+            export_statistics_to_excel,
+            export_matches_by_school,
+            find_students_paged,
+            filter_matches,
+            estimate_matches,
+            check_id_format_compatibility,
+            save_mapping_profile,
+            load_mapping_profiles,
             get_students_match_statistics,
             validate_uploaded_file,
+            validate_difficulty_file,
+            list_worksheets,
+            preview_sheet,
+            get_students,
+            merge_student_files,
+            find_duplicate_students_in_file,
+            clear_roster_cache,
             get_difficulty_type_options,
+            guess_difficulty_type_from_name,
+            get_app_info,
             export_matches_to_excel,
+            export_matches_to_csv,
+            export_matches_to_json,
+            export_matches_multi,
+            export_matches_from_files,
+            open_path_in_default_app,
+            anonymize_file,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,6 +1,18 @@
-use calamine::{DataType, Reader, Xls, XlsError, Xlsx, XlsxError, open_workbook};
+use calamine::{
+    Data, DataType, OdsError, Range, Reader, Sheets, XlsError, XlsxError, open_workbook_from_rs,
+};
+use chardetng::EncodingDetector;
+use chrono::{Duration, Local, NaiveDate, NaiveTime};
+use encoding_rs::{Encoding, UTF_8};
+use rust_xlsxwriter::{Format, Workbook};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek},
+    path::Path,
+};
+use unicode_normalization::UnicodeNormalization;
 
 /// 困难类型枚举
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Copy)]
@@ -44,6 +56,18 @@ impl std::fmt::Display for DifficultyType {
     }
 }
 
+impl std::str::FromStr for DifficultyType {
+    type Err = ExcelError;
+
+    /// 按 `Display` 输出的中文名称精确解析, 需要容错时使用 [`DifficultyType::from_label`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|difficulty_type| difficulty_type.to_string() == s)
+            .ok_or_else(|| ExcelError::UnknownDifficultyType(s.to_string()))
+    }
+}
+
 impl DifficultyType {
     /// 获取所有困难类型
     pub fn all() -> Vec<Self> {
@@ -61,38 +85,201 @@ impl DifficultyType {
         ]
     }
 
-    /// 根据困难类型获取列配置 (身份证列索引, 数据开始行)
+    /// 稳定的英文代码, 用于存储和对接, 不随显示名称变化
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::PovertyAlleviatedContinuePolicy => "PA_CONT",
+            Self::PovertyAlleviatedNoPolicy => "PA_NOPOLICY",
+            Self::DisabledWithCertificate => "DISABLED",
+            Self::RuralMinimumLiving => "RURAL_MIN",
+            Self::UrbanMinimumLiving => "URBAN_MIN",
+            Self::RuralSpecialDifficulty => "SPECIAL_DIFF",
+            Self::AntiPovertyMonitoringRiskNotEliminated => "MONITOR_RISK",
+            Self::AntiPovertyMonitoringRiskEliminated => "MONITOR_CLEARED",
+            Self::OrphansAndFactuallyUnsupportedChildren => "ORPHAN",
+            Self::LowIncomePopulation => "LOW_INCOME",
+        }
+    }
+
+    /// 根据代码解析困难类型
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|difficulty_type| difficulty_type.code() == code)
+    }
+
+    /// 根据中文名称解析困难类型, 忽略空白并兼容全角括号
+    pub fn from_label(s: &str) -> Option<Self> {
+        let label: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                '（' => '(',
+                '）' => ')',
+                c => c,
+            })
+            .collect();
+
+        label.parse().ok()
+    }
+
+    /// 用于从文件内容识别困难类型的关键字, 任一命中即视为候选
+    fn detection_keywords(&self) -> &'static [&'static str] {
+        match self {
+            Self::PovertyAlleviatedContinuePolicy | Self::PovertyAlleviatedNoPolicy => &["脱贫"],
+            Self::DisabledWithCertificate => &["残疾"],
+            Self::RuralMinimumLiving => &["农村低保"],
+            Self::UrbanMinimumLiving => &["城镇低保"],
+            Self::RuralSpecialDifficulty => &["特困"],
+            Self::AntiPovertyMonitoringRiskNotEliminated
+            | Self::AntiPovertyMonitoringRiskEliminated => &["监测"],
+            Self::OrphansAndFactuallyUnsupportedChildren => &["孤儿", "事实无人抚养"],
+            Self::LowIncomePopulation => &["低收入"],
+        }
+    }
+
+    /// 用于区分共用关键字的困难类型的附加关键字
+    fn distinguishing_keywords(&self) -> &'static [&'static str] {
+        match self {
+            Self::PovertyAlleviatedContinuePolicy => &["继续享受"],
+            Self::PovertyAlleviatedNoPolicy => &["不享受"],
+            Self::AntiPovertyMonitoringRiskNotEliminated => &["未消除"],
+            Self::AntiPovertyMonitoringRiskEliminated => &["已消除"],
+            _ => &[],
+        }
+    }
+
+    /// 困难人员数据所在的工作表索引
+    pub fn sheet_index(&self) -> usize {
+        match self {
+            Self::RuralMinimumLiving | Self::UrbanMinimumLiving | Self::RuralSpecialDifficulty => 1,
+            _ => 0,
+        }
+    }
+
+    /// 多列身份证号模板的默认配置, 单列模板返回 `None`
+    pub fn multi_column_config(&self) -> Option<MultiColumnConfig> {
+        let (sheet_index, skip_rows, id_columns) = match self {
+            Self::RuralMinimumLiving => (1, 2, vec![6, 15, 17, 19, 21, 23, 25, 27, 29]),
+            Self::UrbanMinimumLiving => (1, 2, vec![6, 16, 18, 20, 22, 24]),
+            Self::RuralSpecialDifficulty => (1, 3, vec![5, 26, 31, 33, 35, 37, 39, 41]),
+            _ => return None,
+        };
+        Some(MultiColumnConfig {
+            sheet_index,
+            skip_rows,
+            id_columns,
+        })
+    }
+
+    /// 根据困难类型获取列配置 (身份证列索引, 数据开始行), 与读取器使用的布局一致
+    ///
+    /// 多列模板的完整配置见 [`DifficultyType::multi_column_config`]。
     pub fn get_column_config(&self) -> (usize, usize) {
         match self {
             Self::PovertyAlleviatedContinuePolicy | Self::PovertyAlleviatedNoPolicy => (7, 1),
             Self::DisabledWithCertificate => (1, 1),
             Self::RuralMinimumLiving => (6, 2),
-            Self::UrbanMinimumLiving => (6, 1),
+            Self::UrbanMinimumLiving => (6, 2),
             Self::RuralSpecialDifficulty => (5, 3),
             Self::AntiPovertyMonitoringRiskNotEliminated
             | Self::AntiPovertyMonitoringRiskEliminated => (11, 1),
-            Self::OrphansAndFactuallyUnsupportedChildren => (2, 1),
+            Self::OrphansAndFactuallyUnsupportedChildren => (2, 3),
             Self::LowIncomePopulation => (3, 1),
         }
     }
+
+    /// 该困难类型模板的预期布局, 供界面展示以便用户核对文件
+    pub fn layout(&self) -> LayoutInfo {
+        let (sheet_indices, data_start_row, id_columns) = match self.multi_column_config() {
+            Some(config) => (
+                vec![config.sheet_index],
+                config.skip_rows,
+                config.id_columns,
+            ),
+            None => {
+                let (id_column, data_start_row) = self.get_column_config();
+                let sheet_indices = match self {
+                    Self::OrphansAndFactuallyUnsupportedChildren => match OrphanSheets::default() {
+                        OrphanSheets::Indices(indices) => indices,
+                        OrphanSheets::ByName => Vec::new(),
+                    },
+                    _ => vec![self.sheet_index()],
+                };
+                (sheet_indices, data_start_row, vec![id_column])
+            }
+        };
+
+        LayoutInfo {
+            difficulty_type: *self,
+            sheet_indices,
+            data_start_row,
+            id_column_letters: id_columns.iter().map(|&col| column_letter(col)).collect(),
+            id_columns,
+        }
+    }
+}
+
+/// 困难类型模板布局 (索引均从 0 开始)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutInfo {
+    pub difficulty_type: DifficultyType,
+    pub sheet_indices: Vec<usize>,      // 读取的工作表
+    pub data_start_row: usize,          // 未识别到表头时的数据开始行
+    pub id_columns: Vec<usize>,         // 身份证号所在列
+    pub id_column_letters: Vec<String>, // 对应的 Excel 列名, 如 "G"
+}
+
+/// 将列索引 (从 0 开始) 转换为 Excel 列名, 如 0 -> "A", 27 -> "AB"
+fn column_letter(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
 }
 
 /// 学生基本信息结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Student {
-    pub name: String,
-    pub id_number: String,          // 身份证号
+    pub name: String, // 姓名 (已清理空白和零宽字符)
+    #[serde(default)]
+    pub raw_name: String, // 原始姓名 (仅去除首尾空白, 便于回溯源单元格)
+    pub id_number: String, // 身份证号
     pub student_id: Option<String>, // 学号
-    pub class: Option<String>,      // 班级
-    pub grade: Option<String>,      // 年级
-    pub school: Option<String>,     // 学校
+    pub class: Option<String>, // 班级
+    pub grade: Option<String>, // 年级
+    pub school: Option<String>, // 学校
+    #[serde(default)]
+    pub incomplete: bool, // 资料不全 (缺少身份证号), 不参与匹配
 }
 
 /// 困难人员信息结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DifficultPerson {
-    pub id_number: String,               // 身份证号
+    pub id_number: String, // 身份证号 (规范化后, 用于匹配)
+    #[serde(default)]
+    pub raw_id_number: String, // 原始身份证号 (仅去除首尾空白, 便于回溯源单元格)
     pub difficulty_type: DifficultyType, // 困难类型
+    #[serde(default)]
+    pub name: Option<String>, // 姓名 (模板中能识别姓名列时才有)
+}
+
+/// 匹配方式 (新增匹配策略时在此扩展)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchKind {
+    /// 身份证号完全一致
+    #[default]
+    ExactId,
+    /// 身份证号不一致, 按唯一同名学生匹配 (需人工复核)
+    ByName,
+    /// 名册身份证号中间被 `*` 遮挡, 按可见部分唯一匹配 (需人工复核)
+    Masked,
 }
 
 /// 匹配结果结构
@@ -100,419 +287,4496 @@ pub struct DifficultPerson {
 pub struct MatchResult {
     pub student: Student,
     pub difficult_info: DifficultPerson,
+    #[serde(default)]
+    pub match_kind: MatchKind,
+}
+
+/// 匹配选项
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchOptions {
+    /// 身份证号未匹配时, 按姓名进行第二轮匹配
+    ///
+    /// 只对读出姓名的困难人员有效: 需识别到 "姓名" 表头, 多列模板仅户主带姓名。
+    pub fuzzy: bool,
+    /// 严格模式: 困难类型表的列数不足模板要求时报错, 而不是读出空身份证号
+    pub strict: bool,
+    /// 名册身份证号被部分遮挡 (如 "110101********1234") 时, 按未遮挡部分匹配
+    pub masked: bool,
+    /// 匹配前将 15 位旧身份证号统一升级为 18 位, 使新旧号码可以互相匹配
+    pub legacy_ids: bool,
+}
+
+/// 工作表信息结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorksheetInfo {
+    pub name: String,
+    pub index: usize,
+    pub row_count: usize,
+    pub column_count: usize,
 }
 
 /// Excel读取错误类型
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ExcelError {
-    #[error("File not found: {0}")]
+    #[error("文件不存在: {0}")]
     FileNotFound(String),
-    #[error("Read error: {0}")]
-    ReadError(String),
-    #[error("Parse error: {0}")]
-    ParseError(String),
+    #[error("读取 {path} 失败{}: {message}", sheet_context(.sheet))]
+    ReadError {
+        path: String,
+        sheet: Option<usize>, // 工作表索引, 从 0 开始; 与具体工作表无关时为 None
+        message: String,
+    },
+    #[error("解析 {path} 失败: {message}")]
+    ParseError { path: String, message: String },
+    #[error("写入失败: {0}")]
+    WriteError(String),
+    #[error("{path} 缺少第 {} 个工作表 (共 {available} 个工作表)", .index + 1)]
+    SheetNotFound {
+        path: String,
+        index: usize, // 从 0 开始
+        available: usize,
+    },
+    #[error("未知的困难类型: {0}")]
+    UnknownDifficultyType(String),
+    #[error("已取消")]
+    Cancelled,
+}
+
+impl ExcelError {
+    /// 构造带文件路径及工作表上下文的读取错误
+    fn read(path: &str, sheet: Option<usize>, message: impl std::fmt::Display) -> Self {
+        Self::ReadError {
+            path: path.to_string(),
+            sheet,
+            message: message.to_string(),
+        }
+    }
+
+    /// 构造带文件路径的解析错误 (文件可以打开, 但内容不符合预期)
+    fn parse(path: &str, message: impl std::fmt::Display) -> Self {
+        Self::ParseError {
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// 读取错误信息中的工作表描述, 如 " (第 2 个工作表)"
+fn sheet_context(sheet: &Option<usize>) -> String {
+    sheet.map_or_else(String::new, |index| format!(" (第 {} 个工作表)", index + 1))
+}
+
+/// 内存中的文件内容 (如拖放) 没有路径, 错误信息中以此代替
+const IN_MEMORY_PATH: &str = "(内存中的文件)";
+
+/// 不支持的文件扩展名
+const UNSUPPORTED_FORMAT: &str = "不支持的文件格式";
+
+/// 模板配置的工作表在文件中均不存在
+const MISSING_SHEETS: &str = "模板配置的工作表均不存在";
+
+/// 将 xlsx 打开失败的原因转换为可操作的错误信息
+///
+/// 这些损坏情况均无法在程序内修复, 只能提示用户处理:
+/// - 压缩包损坏或实际为其他格式 (如改了扩展名的 xls/csv): 需重新导出或另存为 xlsx;
+/// - 内部 XML 不完整、缺少部件或关系文件 (常见于第三方系统导出): 用 Excel/WPS 打开后另存为可修复;
+/// - 工作簿受密码保护: 需先取消密码。
+///
+/// 其余错误 (如文件被占用) 保留原始信息。
+fn describe_xlsx_error(file_path: &str, error: XlsxError) -> ExcelError {
+    match error {
+        XlsxError::Zip(e) => ExcelError::parse(
+            file_path,
+            format!(
+                "文件不是有效的 xlsx 压缩包, 可能已损坏或实际为其他格式, 请重新导出或另存为 xlsx ({})",
+                e
+            ),
+        ),
+        XlsxError::FileNotFound(part) => ExcelError::parse(
+            file_path,
+            format!("xlsx 文件缺少部件 {}, 请用 Excel 打开后另存为新文件", part),
+        ),
+        XlsxError::RelationshipNotFound => ExcelError::parse(
+            file_path,
+            "xlsx 文件缺少关系文件 (_rels), 请用 Excel 打开后另存为新文件",
+        ),
+        XlsxError::Xml(_)
+        | XlsxError::XmlAttr(_)
+        | XlsxError::XmlAttribute(_)
+        | XlsxError::XmlEof(_)
+        | XlsxError::UnexpectedNode(_)
+        | XlsxError::Unexpected(_)
+        | XlsxError::Encoding(_) => ExcelError::parse(
+            file_path,
+            format!(
+                "xlsx 文件内部 XML 解析失败, 请用 Excel 打开后另存为新文件 ({})",
+                error
+            ),
+        ),
+        XlsxError::Password => ExcelError::parse(file_path, "工作簿受密码保护, 请先取消密码"),
+        error => ExcelError::read(file_path, None, error),
+    }
+}
+
+/// 清理姓名: 先做 NFKC 规范化并去除零宽字符, 中文之间的空白直接删除, 其余连续空白合并为一个空格
+///
+/// 例如 "张 三" -> "张三", "Li  Ming" -> "Li Ming"
+pub fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .nfkc()
+        .filter(|c| !matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}'))
+        .collect();
+
+    let mut result = String::with_capacity(cleaned.len());
+    for word in cleaned.split_whitespace() {
+        let joins_latin = result
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+            && word
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphanumeric());
+        if joins_latin {
+            result.push(' ');
+        }
+        result.push_str(word);
+    }
+    result
 }
 
-/// 清理和标准化身份证号
+/// 清理和标准化身份证号 (NFKC 规范化后去除空白、转为半角大写)
 fn normalize_id_number(id: &str) -> String {
-    id.trim()
+    id.nfkc()
+        .collect::<String>()
+        .trim()
         .replace(" ", "")
         .replace("\t", "")
         .replace("\n", "")
         .replace("\r", "")
+        .chars()
+        .map(to_half_width)
+        .collect::<String>()
         .to_uppercase()
 }
 
-/// 读取学生信息表
-pub fn read_student_info(file_path: &str) -> Result<Vec<Student>, ExcelError> {
-    if !Path::new(file_path).exists() {
-        return Err(ExcelError::FileNotFound(file_path.to_string()));
+/// 将全角数字和全角 X 转换为半角
+fn to_half_width(c: char) -> char {
+    match c {
+        '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+        'Ｘ' | 'ｘ' => 'X',
+        _ => c,
     }
+}
 
-    let mut students = Vec::new();
+/// 将单元格内容转换为身份证号字符串
+///
+/// 部分表格以数字格式存储身份证号, 此时按整数输出, 不带科学计数法和 `.0` 后缀。
+pub fn cell_to_id_string(cell: &Data) -> String {
+    match cell {
+        Data::Float(v) if v.is_finite() && v.fract() == 0.0 => format!("{:.0}", v),
+        Data::Int(v) => v.to_string(),
+        _ => cell.as_string().unwrap_or_default().trim().to_string(),
+    }
+}
 
-    if file_path.ends_with(".xls") {
-        let mut workbook: Xls<_> =
-            open_workbook(file_path).map_err(|e: XlsError| ExcelError::ReadError(e.to_string()))?;
-        let range = workbook
-            .worksheet_range_at(0)
-            .ok_or(ExcelError::ReadError("NO DATA".into()))?
-            .map_err(|e| ExcelError::ReadError(e.to_string()))?;
-
-        for (row_idx, row) in range.rows().enumerate() {
-            if row_idx == 0 {
-                continue; // 跳过表头
-            }
-
-            if row.len() >= 3 {
-                // A列：学生姓名
-                let name = row
-                    .first()
-                    .and_then(|v| v.as_string())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
-
-                // B列：身份证件号
-                let id_value = row.get(1);
-                let id_number = id_value
-                    .and_then(|v| v.as_string())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
-
-                if !name.is_empty() && !id_number.is_empty() {
-                    let normalized_id = normalize_id_number(&id_number);
-                    let student = Student {
-                        name: name.clone(),
-                        id_number: normalized_id.clone(),
-                        // K列：全国学籍号
-                        student_id: row
-                            .get(10)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                        // J列：班级
-                        class: row
-                            .get(9)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                        // I列：年级
-                        grade: row
-                            .get(8)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                        // E列：学校名称
-                        school: row
-                            .get(4)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                    };
-                    students.push(student);
-                }
+/// 将单元格转换为显示字符串, 空单元格、空白文本和错误值返回 None
+///
+/// 整数值的数字不带小数点, 日期按 `YYYY-MM-DD` 输出 (含时间时追加 `HH:MM:SS`)。
+pub fn cell_to_display_string(cell: &Data) -> Option<String> {
+    let text = match cell {
+        Data::Empty | Data::Error(_) => return None,
+        Data::String(s) => s.trim().to_string(),
+        Data::Float(_) | Data::Int(_) => cell_to_id_string(cell),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(value) => match value.as_datetime() {
+            Some(datetime) if value.is_datetime() && datetime.time() == NaiveTime::MIN => {
+                datetime.format("%Y-%m-%d").to_string()
             }
-        }
-    } else if file_path.ends_with(".xlsx") {
-        let mut workbook: Xlsx<_> = open_workbook(file_path)
-            .map_err(|e: XlsxError| ExcelError::ReadError(e.to_string()))?;
-        let range = workbook
-            .worksheet_range_at(0)
-            .ok_or(ExcelError::ReadError("NO DATA".into()))?
-            .map_err(|e| ExcelError::ReadError(e.to_string()))?;
-
-        for (row_idx, row) in range.rows().enumerate() {
-            if row_idx == 0 {
-                continue; // 跳过表头
-            }
-
-            if row.len() >= 3 {
-                // A列：学生姓名
-                let name = row
-                    .first()
-                    .and_then(|v| v.as_string())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
-
-                // B列：身份证件号
-                let id_value = row.get(1);
-                let id_number = id_value
-                    .and_then(|v| v.as_string())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
-
-                if !name.is_empty() && !id_number.is_empty() {
-                    let normalized_id = normalize_id_number(&id_number);
-                    let student = Student {
-                        name: name.clone(),
-                        id_number: normalized_id.clone(),
-                        // K列：全国学籍号
-                        student_id: row
-                            .get(10)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                        // J列：班级
-                        class: row
-                            .get(9)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                        // I列：年级
-                        grade: row
-                            .get(8)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                        // E列：学校名称
-                        school: row
-                            .get(4)
-                            .and_then(|v| v.as_string())
-                            .map(|s| s.trim().to_string()),
-                    };
-                    students.push(student);
-                }
+            Some(datetime) if value.is_datetime() => {
+                datetime.format("%Y-%m-%d %H:%M:%S").to_string()
             }
+            _ => value.to_string(),
+        },
+        Data::DateTimeIso(s) | Data::DurationIso(s) => s.trim().to_string(),
+    };
+    Some(text).filter(|s| !s.is_empty())
+}
+
+/// 判断字符串是否符合身份证号的基本形态 (18 位或 15 位)
+pub fn looks_like_id_number(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    match bytes.len() {
+        18 => {
+            bytes[..17].iter().all(u8::is_ascii_digit)
+                && (bytes[17].is_ascii_digit() || bytes[17] == b'X')
+        }
+        15 => bytes.iter().all(u8::is_ascii_digit),
+        _ => false,
+    }
+}
+
+/// 身份证号的形态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdFormat {
+    Full18,   // 18 位身份证号
+    Legacy15, // 15 位旧身份证号
+    Masked,   // 部分遮挡的身份证号
+    Other,    // 不符合身份证号形态
+}
+
+/// 是否为身份证号中的遮挡字符 (`*` 或全角 `＊`)
+fn is_id_mask(c: char) -> bool {
+    c == '*' || c == '＊'
+}
+
+/// 判断身份证号 (已标准化) 的形态
+pub fn id_format(id: &str) -> IdFormat {
+    if looks_like_id_number(id) {
+        if id.len() == 18 {
+            IdFormat::Full18
+        } else {
+            IdFormat::Legacy15
         }
+    } else if matches!(id.chars().count(), 15 | 18)
+        && id.chars().any(is_id_mask)
+        && id
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == 'X' || is_id_mask(c))
+    {
+        IdFormat::Masked
     } else {
-        return Err(ExcelError::ReadError("NO DATA".to_string()));
+        IdFormat::Other
     }
-    Ok(students)
 }
 
-/// 常规
-fn read_common(
-    file_path: &str,
-    difficulty_type: DifficultyType,
-) -> Result<Vec<DifficultPerson>, ExcelError> {
-    let mut difficult_people = Vec::new();
+/// 根据 18 位身份证号的前 17 位计算校验位
+pub fn id_check_digit(first17: &str) -> Option<char> {
+    const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+    const CHECK_DIGITS: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
 
-    // 根据困难类型确定列位置
-    let (id_col, data_start_row) = difficulty_type.get_column_config();
+    if first17.len() != 17 {
+        return None;
+    }
 
-    let mut workbook: Xlsx<_> =
-        open_workbook(file_path).map_err(|e: XlsxError| ExcelError::ReadError(e.to_string()))?;
-    let range = workbook
-        .worksheet_range_at(0)
-        .ok_or(ExcelError::ReadError(
-            "Cannot find worksheet at index 0".to_string(),
-        ))?
-        .map_err(|e| ExcelError::ReadError(e.to_string()))?;
+    let mut sum = 0;
+    for (c, weight) in first17.chars().zip(WEIGHTS) {
+        sum += c.to_digit(10)? * weight;
+    }
+    Some(CHECK_DIGITS[(sum % 11) as usize])
+}
 
-    for row in range.rows().skip(data_start_row) {
-        let id_number = row
-            .get(id_col)
-            .and_then(|v| v.as_string())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
+/// 统一身份证号形式: 15 位旧号码升级为 18 位, 其他号码保持不变
+pub fn canonicalize_id(id: &str) -> String {
+    upgrade_legacy_id(id).unwrap_or_else(|| id.to_string())
+}
 
-        // 只要身份证号不为空就添加记录
-        if !id_number.is_empty() {
-            let difficult_person = DifficultPerson {
-                id_number: normalize_id_number(&id_number),
-                difficulty_type,
-            };
-            difficult_people.push(difficult_person);
+/// 将 15 位旧身份证号升级为 18 位 (补充世纪 "19" 并计算校验码)
+fn upgrade_legacy_id(id: &str) -> Option<String> {
+    if id.len() != 15 || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let first17 = format!("{}19{}", &id[..6], &id[6..]);
+    let check_digit = id_check_digit(&first17)?;
+    Some(format!("{}{}", first17, check_digit))
+}
+
+/// 性别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gender {
+    #[serde(rename = "男")]
+    Male,
+    #[serde(rename = "女")]
+    Female,
+}
+
+impl std::fmt::Display for Gender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Male => write!(f, "男"),
+            Self::Female => write!(f, "女"),
         }
     }
-    Ok(difficult_people)
 }
 
-/// 孤儿
-fn read_orphans(file_path: &str) -> Result<Vec<DifficultPerson>, ExcelError> {
-    let mut difficult_people = Vec::new();
+/// 由身份证号解析出的出生日期、性别与年龄
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdDemographics {
+    pub birth_date: NaiveDate,
+    pub gender: Gender,
+    pub age: u32, // 周岁
+}
 
-    let mut workbook: Xls<_> =
-        open_workbook(file_path).map_err(|e: XlsError| ExcelError::ReadError(e.to_string()))?;
-    let range = workbook
-        .worksheet_range_at(0)
-        .ok_or(ExcelError::ReadError("NO DATA".to_string()))?
-        .map_err(|e| ExcelError::ReadError(e.to_string()))?;
+/// 从身份证号解析出生日期、性别与当前年龄, 15 位旧号码先升级为 18 位
+pub fn parse_id_demographics(id: &str) -> Option<IdDemographics> {
+    parse_id_demographics_at(id, Local::now().date_naive())
+}
 
-    for row in range.rows().skip(3) {
-        let id_number = row
-            .get(2)
-            .and_then(|v| v.as_string())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
+/// 从身份证号解析出生日期、性别, 年龄按给定日期计算
+pub fn parse_id_demographics_at(id: &str, today: NaiveDate) -> Option<IdDemographics> {
+    let id = normalize_id_number(id);
+    if !looks_like_id_number(&id) {
+        return None;
+    }
+    let id = if id.len() == 15 {
+        upgrade_legacy_id(&id)?
+    } else {
+        id
+    };
 
-        // 只要身份证号不为空就添加记录
-        if !id_number.is_empty() {
-            let difficult_person = DifficultPerson {
-                id_number: normalize_id_number(&id_number),
-                difficulty_type: DifficultyType::OrphansAndFactuallyUnsupportedChildren,
-            };
-            difficult_people.push(difficult_person);
+    // 第 7~14 位为出生日期, 第 17 位奇数为男、偶数为女
+    let birth_date = NaiveDate::parse_from_str(&id[6..14], "%Y%m%d").ok()?;
+    let gender = if id.as_bytes()[16] % 2 == 1 {
+        Gender::Male
+    } else {
+        Gender::Female
+    };
+    let age = today.years_since(birth_date)?;
+
+    Some(IdDemographics {
+        birth_date,
+        gender,
+        age,
+    })
+}
+
+/// 表头识别时扫描的最大行数
+const HEADER_SCAN_ROWS: usize = 10;
+
+/// 困难类型表身份证列的表头关键字
+const ID_HEADER_LABELS: &[&str] = &["身份证"];
+
+/// 在前若干行中查找表头行
+///
+/// 某行的单元格 (忽略空白) 包含全部期望的表头文字即视为表头行。
+/// 多行表头时返回最后一个匹配行, 遇到含身份证号的数据行后停止查找。
+pub fn find_header_row(range: &Range<Data>, expected_headers: &[&str]) -> Option<usize> {
+    let mut header_row = None;
+
+    for (row_idx, row) in range.rows().take(HEADER_SCAN_ROWS).enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .filter_map(|v| v.as_string())
+            .map(|s| s.split_whitespace().collect())
+            .collect();
+
+        if cells
+            .iter()
+            .any(|cell| looks_like_id_number(&normalize_id_number(cell)))
+        {
+            break;
+        }
+
+        if expected_headers
+            .iter()
+            .all(|header| cells.iter().any(|cell| cell.contains(header)))
+        {
+            header_row = Some(row_idx);
         }
     }
-    let range = workbook
-        .worksheet_range_at(2)
-        .ok_or(ExcelError::ReadError("NO DATA".to_string()))?
-        .map_err(|e| ExcelError::ReadError(e.to_string()))?;
 
-    for row in range.rows().skip(3) {
-        let id_number = row
-            .get(2)
-            .and_then(|v| v.as_string())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
+    header_row
+}
 
-        // 只要身份证号不为空就添加记录
-        if !id_number.is_empty() {
-            let difficult_person = DifficultPerson {
-                id_number: normalize_id_number(&id_number),
-                difficulty_type: DifficultyType::OrphansAndFactuallyUnsupportedChildren,
-            };
-            difficult_people.push(difficult_person);
-        }
+/// 在表头区域 (前 header_row + 1 行) 中查找包含指定文字的列, 多行表头时取最后一个
+fn find_header_column(range: &Range<Data>, header_row: usize, label: &str) -> Option<usize> {
+    range
+        .rows()
+        .take(header_row + 1)
+        .filter_map(|row| {
+            row.iter()
+                .position(|cell| cell.as_string().is_some_and(|v| v.contains(label)))
+        })
+        .next_back()
+}
+
+/// 查找姓名列 (仅在识别到表头时)
+fn find_name_column(range: &Range<Data>) -> Option<usize> {
+    find_header_row(range, ID_HEADER_LABELS).and_then(|row| find_header_column(range, row, "姓名"))
+}
+
+/// 读取可选的姓名单元格, 与学生名册相同按 [`normalize_name`] 规范化
+fn read_name_cell(row: &[Data], name_col: Option<usize>) -> Option<String> {
+    name_col
+        .and_then(|col| row.get(col))
+        .and_then(cell_to_display_string)
+        .map(|name| normalize_name(&name))
+        .filter(|name| !name.is_empty())
+}
+
+/// 确定数据开始行: 优先使用识别到的表头, 否则回退到模板默认值
+fn find_data_start_row(range: &Range<Data>, default_start_row: usize) -> usize {
+    find_header_row(range, ID_HEADER_LABELS).map_or(default_start_row, |row| row + 1)
+}
+
+/// 工作表索引超出文件中工作表数量时的错误
+fn sheet_index_out_of_range(file_path: &str, sheet_index: usize, sheet_count: usize) -> ExcelError {
+    ExcelError::SheetNotFound {
+        path: file_path.to_string(),
+        index: sheet_index,
+        available: sheet_count,
     }
-    Ok(difficult_people)
 }
 
-/// 农村低保
-fn read_rural_minimum_living(file_path: &str) -> Result<Vec<DifficultPerson>, ExcelError> {
-    let mut difficult_people = Vec::new();
-    let difficulty_type = DifficultyType::RuralMinimumLiving;
-
-    let mut workbook: Xls<_> = open_workbook(file_path).unwrap();
-    let range = workbook
-        .worksheet_range_at(1)
-        .ok_or(ExcelError::ReadError(
-            "Cannot find worksheet at index 0".to_string(),
-        ))?
-        .map_err(|e| ExcelError::ReadError(e.to_string()))?;
-    let id_columns = [6, 15, 17, 19, 21, 23, 25, 27, 29];
-    for row in range.rows().skip(2) {
-        for col in id_columns {
-            let raw_value = row.get(col);
-            let id_number = raw_value
-                .and_then(|v| v.as_string())
-                .unwrap_or_default()
-                .trim()
-                .to_string();
+/// 文件扩展名是否为 `extension` (不区分大小写, 如 "名册.XLSX")
+fn has_extension(file_path: &str, extension: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
 
-            if !id_number.is_empty() {
-                let normalized_id = normalize_id_number(&id_number);
-                let difficult_person = DifficultPerson {
-                    id_number: normalized_id,
-                    difficulty_type,
-                };
-                difficult_people.push(difficult_person);
+/// 是否为分隔符文本文件 (.txt 按制表符分隔, .csv 按逗号分隔)
+fn text_delimiter_for_path(file_path: &str) -> Option<char> {
+    if has_extension(file_path, "txt") {
+        Some('\t')
+    } else if has_extension(file_path, "csv") {
+        Some(',')
+    } else {
+        None
+    }
+}
+
+/// 解码文本内容: 有 BOM 时按 BOM, 合法的 UTF-8 按 UTF-8, 否则由 chardetng 推测 (中文 Windows 下多为 GBK)
+fn decode_text(bytes: &[u8]) -> (String, &'static Encoding) {
+    let encoding = if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        encoding
+    } else if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        detector.guess(None, true)
+    };
+    let (text, _, _) = encoding.decode(bytes);
+    (text.into_owned(), encoding)
+}
+
+/// 读取文本文件并识别编码
+fn read_text_file(file_path: &str) -> Result<(String, &'static Encoding), ExcelError> {
+    if !Path::new(file_path).exists() {
+        return Err(ExcelError::FileNotFound(file_path.to_string()));
+    }
+    let bytes = std::fs::read(file_path).map_err(|e| ExcelError::read(file_path, None, e))?;
+    Ok(decode_text(&bytes))
+}
+
+/// 识别分隔符文本文件的编码, 返回编码名称 (如 "UTF-8"、"GBK")
+pub fn detect_text_encoding(file_path: &str) -> Result<String, ExcelError> {
+    let (_, encoding) = read_text_file(file_path)?;
+    Ok(encoding.name().to_string())
+}
+
+/// 将分隔符文本文件读取为单个工作表, 空字段视为空单元格
+fn read_delimited_range(file_path: &str, delimiter: char) -> Result<Range<Data>, ExcelError> {
+    let (text, _) = read_text_file(file_path)?;
+    Ok(parse_delimited_text(&text, delimiter))
+}
+
+/// 将分隔符文本解析为单个工作表
+fn parse_delimited_text(text: &str, delimiter: char) -> Range<Data> {
+    let rows = split_delimited_records(text.trim_start_matches('\u{feff}'), delimiter);
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if rows.is_empty() || width == 0 {
+        return Range::empty();
+    }
+
+    let mut range = Range::new((0, 0), (rows.len() as u32 - 1, width as u32 - 1));
+    for (row_idx, row) in rows.iter().enumerate() {
+        for (col_idx, field) in row.iter().enumerate() {
+            if !field.is_empty() {
+                range.set_value(
+                    (row_idx as u32, col_idx as u32),
+                    Data::String(field.to_string()),
+                );
             }
         }
     }
-    Ok(difficult_people)
+    range
 }
 
-/// 城镇低保
-fn read_urban_minimum_living(file_path: &str) -> Result<Vec<DifficultPerson>, ExcelError> {
-    let mut difficult_people = Vec::new();
-    let difficulty_type = DifficultyType::UrbanMinimumLiving;
-
-    let mut workbook: Xls<_> = open_workbook(file_path).unwrap();
-    let range = workbook
-        .worksheet_range_at(1)
-        .ok_or(ExcelError::ReadError(
-            "Cannot find worksheet at index 0".to_string(),
-        ))?
-        .map_err(|e| ExcelError::ReadError(e.to_string()))?;
-    let id_columns = [6, 16, 18, 20, 22, 24];
-    for row in range.rows().skip(2) {
-        for col in id_columns {
-            let raw_value = row.get(col);
-            let id_number = raw_value
-                .and_then(|v| v.as_string())
-                .unwrap_or_default()
-                .trim()
-                .to_string();
+/// 按 RFC 4180 拆分分隔符文本, 字段去除首尾空白
+///
+/// 引号内的分隔符和换行属于字段内容, 引号内的 `""` 表示一个引号; 空行保留为只有一个空字段的记录。
+fn split_delimited_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
 
-            if !id_number.is_empty() {
-                let difficult_person = DifficultPerson {
-                    id_number: normalize_id_number(&id_number),
-                    difficulty_type,
-                };
-                difficult_people.push(difficult_person);
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c != '"' {
+                field.push(c);
+            } else if chars.next_if_eq(&'"').is_some() {
+                field.push('"');
+            } else {
+                in_quotes = false;
             }
+        } else if c == '"' && field.trim().is_empty() {
+            // 字段以引号开头 (允许前导空白)
+            field.clear();
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(field.trim().to_string());
+            field.clear();
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' {
+                chars.next_if_eq(&'\n');
+            }
+            record.push(field.trim().to_string());
+            field.clear();
+            records.push(std::mem::take(&mut record));
+        } else {
+            field.push(c);
         }
     }
-    Ok(difficult_people)
+
+    // 最后一行没有换行符
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field.trim().to_string());
+        records.push(record);
+    }
+    records
 }
 
-/// 城乡特困
-fn read_rural_special_difficulty(file_path: &str) -> Result<Vec<DifficultPerson>, ExcelError> {
-    let mut difficult_people = Vec::new();
+/// 支持的工作簿格式 (分隔符文本文件见 [`text_delimiter_for_path`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkbookFormat {
+    Xls,
+    Xlsx,
+    Ods,
+}
 
-    let mut workbook: Xlsx<_> =
-        open_workbook(file_path).map_err(|e: XlsxError| ExcelError::ReadError(e.to_string()))?;
-    let range = workbook
-        .worksheet_range_at(1)
-        .ok_or(ExcelError::ReadError(
-            "Cannot find worksheet at index 1".to_string(),
-        ))?
-        .map_err(|e| ExcelError::ReadError(e.to_string()))?;
-
-    let id_columns = [5, 26, 31, 33, 35, 37, 39, 41];
-    for row in range.rows().skip(3) {
-        for col in id_columns {
-            let id_number = row
-                .get(col)
-                .and_then(|v| v.as_string())
-                .unwrap_or_default()
-                .trim()
-                .to_string();
+impl WorkbookFormat {
+    /// 按扩展名确定格式 (启用宏的 .xlsm 与 .xlsx 结构相同)
+    fn from_path(file_path: &str) -> Option<Self> {
+        if has_extension(file_path, "xls") {
+            Some(Self::Xls)
+        } else if has_extension(file_path, "xlsx") || has_extension(file_path, "xlsm") {
+            Some(Self::Xlsx)
+        } else if has_extension(file_path, "ods") {
+            Some(Self::Ods)
+        } else {
+            None
+        }
+    }
 
-            if !id_number.is_empty() {
-                let difficult_person = DifficultPerson {
-                    id_number: normalize_id_number(&id_number),
-                    difficulty_type: DifficultyType::RuralSpecialDifficulty,
-                };
-                difficult_people.push(difficult_person);
-            }
+    /// 按该格式打开工作簿, `path` 用于错误信息
+    fn open<RS: Read + Seek>(self, reader: RS, path: &str) -> Result<Sheets<RS>, ExcelError> {
+        match self {
+            Self::Xls => open_workbook_from_rs(reader)
+                .map(Sheets::Xls)
+                .map_err(|e: XlsError| ExcelError::read(path, None, e)),
+            Self::Xlsx => open_workbook_from_rs(reader)
+                .map(Sheets::Xlsx)
+                .map_err(|e| describe_xlsx_error(path, e)),
+            Self::Ods => open_workbook_from_rs(reader)
+                .map(Sheets::Ods)
+                .map_err(|e: OdsError| ExcelError::read(path, None, e)),
         }
     }
-    Ok(difficult_people)
 }
 
-/// 读取困难类型表
-pub fn read_difficult_type_table(
-    file_path: &str,
-    difficulty_type: DifficultyType,
-) -> Result<Vec<DifficultPerson>, ExcelError> {
+/// 按扩展名打开工作簿文件 (xls、xlsx/xlsm、ods), 分隔符文本文件不在此处理
+fn open_spreadsheet(file_path: &str) -> Result<Sheets<BufReader<File>>, ExcelError> {
     if !Path::new(file_path).exists() {
         return Err(ExcelError::FileNotFound(file_path.to_string()));
     }
+    let format = WorkbookFormat::from_path(file_path)
+        .ok_or_else(|| ExcelError::read(file_path, None, UNSUPPORTED_FORMAT))?;
+    let file = File::open(file_path).map_err(|e| ExcelError::read(file_path, None, e))?;
+    format.open(BufReader::new(file), file_path)
+}
 
-    match difficulty_type {
-        DifficultyType::RuralMinimumLiving => read_rural_minimum_living(file_path),
-        DifficultyType::RuralSpecialDifficulty => read_rural_special_difficulty(file_path),
-        DifficultyType::UrbanMinimumLiving => read_urban_minimum_living(file_path),
-        DifficultyType::OrphansAndFactuallyUnsupportedChildren => read_orphans(file_path),
-        _ => read_common(file_path, difficulty_type),
+/// 按索引读取已打开工作簿中的工作表
+fn workbook_range_at<RS: Read + Seek>(
+    workbook: &mut Sheets<RS>,
+    file_path: &str,
+    sheet_index: usize,
+) -> Result<Range<Data>, ExcelError> {
+    let sheet_count = workbook.sheet_names().len();
+    workbook
+        .worksheet_range_at(sheet_index)
+        .ok_or_else(|| sheet_index_out_of_range(file_path, sheet_index, sheet_count))?
+        .map_err(|e| ExcelError::read(file_path, Some(sheet_index), e))
+}
+
+/// 按索引读取工作表 (分隔符文本文件视为只有一个工作表, 其余见 [`open_spreadsheet`])
+fn open_sheet_range(file_path: &str, sheet_index: usize) -> Result<Range<Data>, ExcelError> {
+    if let Some(delimiter) = text_delimiter_for_path(file_path) {
+        if sheet_index > 0 {
+            return Err(sheet_index_out_of_range(file_path, sheet_index, 1));
+        }
+        return read_delimited_range(file_path, delimiter);
     }
+
+    let mut workbook = open_spreadsheet(file_path)?;
+    workbook_range_at(&mut workbook, file_path, sheet_index)
 }
 
-/// 匹配学生信息和困难类型信息
-pub fn match_students_with_difficulty(
-    students: &[Student],
-    difficult_people: &[DifficultPerson],
-) -> Vec<MatchResult> {
-    let mut results = Vec::new();
+/// 按名称读取工作表
+fn open_sheet_range_by_name(file_path: &str, sheet_name: &str) -> Result<Range<Data>, ExcelError> {
+    let mut workbook = open_spreadsheet(file_path)?;
+    if !workbook.sheet_names().iter().any(|name| name == sheet_name) {
+        return Err(ExcelError::read(
+            file_path,
+            None,
+            format!("找不到工作表: {}", sheet_name),
+        ));
+    }
+    workbook
+        .worksheet_range(sheet_name)
+        .map_err(|e| ExcelError::read(file_path, None, format!("工作表 {}: {}", sheet_name, e)))
+}
 
-    // 创建学生身份证号的哈希映射以提高查询效率
-    let student_map: HashMap<String, &Student> =
-        students.iter().map(|s| (s.id_number.clone(), s)).collect();
+/// 学生信息表列配置 (列索引从 0 开始)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StudentColumnConfig {
+    pub name: usize,       // 学生姓名
+    pub id_number: usize,  // 身份证号
+    pub school: usize,     // 学校
+    pub grade: usize,      // 年级
+    pub class: usize,      // 班级
+    pub student_id: usize, // 学号
+    pub header_row: usize, // 表头所在行, 数据从下一行开始
+    #[serde(default)]
+    pub include_incomplete: bool, // 保留有姓名但缺少身份证号的行, 标记为资料不全
+}
 
-    for difficult_person in difficult_people {
-        if let Some(student) = student_map.get(&difficult_person.id_number) {
-            results.push(MatchResult {
-                student: (*student).clone(),
-                difficult_info: difficult_person.clone(),
-            });
+impl Default for StudentColumnConfig {
+    fn default() -> Self {
+        Self {
+            name: 0,        // A列：学生姓名
+            id_number: 1,   // B列：身份证件号
+            school: 4,      // E列：学校名称
+            grade: 8,       // I列：年级
+            class: 9,       // J列：班级
+            student_id: 10, // K列：全国学籍号
+            header_row: 0,
+            include_incomplete: false,
         }
     }
+}
+
+/// 将单元格转换为预览用的显示字符串 (错误值原样显示)
+fn cell_to_preview_string(cell: &Data) -> String {
+    cell_to_display_string(cell).unwrap_or_else(|| cell.to_string())
+}
+
+/// 读取工作表的前若干行, 每个单元格转换为显示字符串
+pub fn preview_sheet(
+    file_path: &str,
+    sheet_index: usize,
+    rows: usize,
+) -> Result<Vec<Vec<String>>, ExcelError> {
+    let range = open_sheet_range(file_path, sheet_index)?;
+    Ok(range
+        .rows()
+        .take(rows)
+        .map(|row| row.iter().map(cell_to_preview_string).collect())
+        .collect())
+}
+
+/// 统计工作表中表头之后的非空数据行数
+///
+/// 未识别到身份证表头时, 视第一行为表头。
+pub fn count_data_rows(file_path: &str, sheet_index: usize) -> Result<usize, ExcelError> {
+    let range = open_sheet_range(file_path, sheet_index)?;
+    let data_start_row = find_data_start_row(&range, 1);
+    Ok(range
+        .rows()
+        .skip(data_start_row)
+        .filter(|row| row.iter().any(|cell| !cell.is_empty()))
+        .count())
+}
+
+/// 列出工作簿中所有工作表的名称与尺寸
+pub fn read_worksheet_infos(file_path: &str) -> Result<Vec<WorksheetInfo>, ExcelError> {
+    let mut workbook = open_spreadsheet(file_path)?;
+    workbook
+        .sheet_names()
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let range = workbook_range_at(&mut workbook, file_path, index)?;
+            Ok(WorksheetInfo {
+                name,
+                index,
+                row_count: range.height(),
+                column_count: range.width(),
+            })
+        })
+        .collect()
+}
+
+/// 根据工作表名称和表头内容识别困难类型
+///
+/// 仅当能唯一确定时返回 `Some`, 候选列表见 [`detect_difficulty_type_candidates`]。
+pub fn detect_difficulty_type(file_path: &str) -> Option<DifficultyType> {
+    match detect_difficulty_type_candidates(file_path) {
+        Ok(candidates) if candidates.len() == 1 => candidates.first().copied(),
+        _ => None,
+    }
+}
 
-    results
+/// 根据工作表名称和各工作表前几行内容列出可能的困难类型
+pub fn detect_difficulty_type_candidates(
+    file_path: &str,
+) -> Result<Vec<DifficultyType>, ExcelError> {
+    let mut workbook = open_spreadsheet(file_path)?;
+    let text = collect_header_text(&mut workbook);
+    Ok(difficulty_type_candidates_in_text(&text))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// 按关键字列出文本中提及的困难类型
+fn difficulty_type_candidates_in_text(text: &str) -> Vec<DifficultyType> {
+    let candidates: Vec<DifficultyType> = DifficultyType::all()
+        .into_iter()
+        .filter(|t| t.detection_keywords().iter().any(|k| text.contains(k)))
+        .collect();
 
-    #[test]
-    fn test_read() {
-        let result = read_rural_minimum_living("/Users/xiaoyu/Downloads/2025年秋季学期困难类型查询专用表及最新的本县户籍特殊困难群体人员信息9.3/3-02025.9.1-2025年9月份农村低保备案表.xls").unwrap();
-        println!("农村低保数量 {}", result.len());
-        let result = read_rural_special_difficulty("/Users/xiaoyu/Downloads/2025年秋季学期困难类型查询专用表及最新的本县户籍特殊困难群体人员信息9.3/4-2025.9.1-2025年9月份城乡特困人员备案表.xlsx").unwrap();
-        println!("特困人员数量 {}", result.len());
+    // 多个候选共用关键字时, 尝试用附加关键字进一步区分
+    let narrowed: Vec<DifficultyType> = candidates
+        .iter()
+        .copied()
+        .filter(|t| t.distinguishing_keywords().iter().any(|k| text.contains(k)))
+        .collect();
 
-        let result = read_urban_minimum_living("/Users/xiaoyu/Downloads/2025年秋季学期困难类型查询专用表及最新的本县户籍特殊困难群体人员信息9.3/5-2025.9.1-2025年9月份城镇低保全部备案表.xls").unwrap();
-        println!("城镇低保人员数量 {}", result.len());
+    if candidates.len() > 1 && !narrowed.is_empty() {
+        narrowed
+    } else {
+        candidates
     }
+}
 
-    #[test]
-    fn test_read_orphans() {
-        let file_path = "/Users/xiaoyu/Downloads/2025年秋季学期困难类型查询专用表及最新的本县户籍特殊困难群体人员信息9.3/7-2025.9.1-2025年9月份孤儿及事实无人抚养儿童发放花名册.xls";
-        let result = read_orphans(file_path).unwrap();
-        println!("数量: {}", result.len());
+/// 根据文件名关键字推断困难类型, 无法唯一确定时返回 None
+///
+/// 例如 "2025年9月份农村低保备案表.xls" -> 农村低保
+pub fn guess_difficulty_type_from_file_name(file_name: &str) -> Option<DifficultyType> {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map_or(file_name.into(), |stem| stem.to_string_lossy());
+    let text: String = stem.chars().filter(|c| !c.is_whitespace()).collect();
+
+    match difficulty_type_candidates_in_text(&text).as_slice() {
+        [difficulty_type] => Some(*difficulty_type),
+        _ => None,
+    }
+}
+
+/// 收集所有工作表名称及各表前几行的文本 (去除空白)
+fn collect_header_text<RS: Read + Seek>(workbook: &mut Sheets<RS>) -> String {
+    let sheet_names = workbook.sheet_names();
+    let mut text: String = sheet_names.concat();
+
+    for index in 0..sheet_names.len() {
+        if let Some(Ok(range)) = workbook.worksheet_range_at(index) {
+            for row in range.rows().take(HEADER_SCAN_ROWS) {
+                for cell in row.iter().filter_map(|v| v.as_string()) {
+                    text.push('\n');
+                    text.extend(cell.chars().filter(|c| !c.is_whitespace()));
+                }
+            }
+        }
+    }
+
+    text
+}
+
+/// 读取学生信息表
+pub fn read_student_info(file_path: &str) -> Result<Vec<Student>, ExcelError> {
+    read_student_info_with_config(file_path, &StudentColumnConfig::default())
+}
+
+/// 按列配置读取学生信息表
+pub fn read_student_info_with_config(
+    file_path: &str,
+    config: &StudentColumnConfig,
+) -> Result<Vec<Student>, ExcelError> {
+    read_student_info_at(file_path, 0, config)
+}
+
+/// 读取指定工作表中的学生信息 (适用于首页为封面的名册)
+pub fn read_student_info_at(
+    file_path: &str,
+    sheet_index: usize,
+    config: &StudentColumnConfig,
+) -> Result<Vec<Student>, ExcelError> {
+    let range = open_sheet_range(file_path, sheet_index)?;
+    Ok(parse_student_rows(&range, config))
+}
+
+/// 分隔符文本名册的读取结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextRoster {
+    pub students: Vec<Student>,
+    pub encoding: String, // 识别出的文件编码, 如 "UTF-8"、"GBK"
+}
+
+/// 读取分隔符文本格式的学生名册 (制表符或逗号分隔), 列对应关系与 Excel 名册相同
+pub fn read_student_info_from_txt(
+    file_path: &str,
+    delimiter: char,
+) -> Result<TextRoster, ExcelError> {
+    let (text, encoding) = read_text_file(file_path)?;
+    let range = parse_delimited_text(&text, delimiter);
+    Ok(TextRoster {
+        students: parse_student_rows(&range, &StudentColumnConfig::default()),
+        encoding: encoding.name().to_string(),
+    })
+}
+
+/// 合并后的学生名册
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergedRoster {
+    pub students: Vec<Student>,
+    pub conflicting_ids: Vec<String>, // 同一身份证号对应不同姓名
+}
+
+/// 读取并合并多个学生信息表, 按身份证号去重
+pub fn read_student_info_multi(paths: &[String]) -> Result<Vec<Student>, ExcelError> {
+    Ok(merge_student_rosters(paths)?.students)
+}
+
+/// 读取并合并多个学生信息表
+///
+/// 同一身份证号只保留首次出现的记录, 其缺失的学号、班级等字段用后续记录补全;
+/// 姓名不一致的身份证号会记入 `conflicting_ids`。
+pub fn merge_student_rosters(paths: &[String]) -> Result<MergedRoster, ExcelError> {
+    let mut roster = MergedRoster::default();
+    let mut index_by_id: HashMap<String, usize> = HashMap::new();
+    let mut conflicts = HashSet::new();
+
+    for path in paths {
+        for student in read_student_info(path)? {
+            let Some(&index) = index_by_id.get(&student.id_number) else {
+                index_by_id.insert(student.id_number.clone(), roster.students.len());
+                roster.students.push(student);
+                continue;
+            };
+
+            let existing = &mut roster.students[index];
+            if existing.name != student.name && conflicts.insert(student.id_number.clone()) {
+                roster.conflicting_ids.push(student.id_number.clone());
+            }
+
+            let fill = |current: &mut Option<String>, other: Option<String>| {
+                if current.as_deref().is_none_or(str::is_empty) {
+                    *current = other;
+                }
+            };
+            fill(&mut existing.student_id, student.student_id);
+            fill(&mut existing.class, student.class);
+            fill(&mut existing.grade, student.grade);
+            fill(&mut existing.school, student.school);
+        }
+    }
+
+    Ok(roster)
+}
+
+/// 从内存中的文件内容读取学生信息表 (用于拖放等无法获得文件路径的场景)
+pub fn read_student_info_from_bytes(
+    bytes: &[u8],
+    is_xlsx: bool,
+) -> Result<Vec<Student>, ExcelError> {
+    let format = if is_xlsx {
+        WorkbookFormat::Xlsx
+    } else {
+        WorkbookFormat::Xls
+    };
+    let mut workbook = format.open(Cursor::new(bytes), IN_MEMORY_PATH)?;
+    let range = workbook_range_at(&mut workbook, IN_MEMORY_PATH, 0)?;
+
+    Ok(parse_student_rows(&range, &StudentColumnConfig::default()))
+}
+
+/// 按列配置解析学生信息行
+fn parse_student_rows(range: &Range<Data>, config: &StudentColumnConfig) -> Vec<Student> {
+    let optional_cell = |row: &[Data], col: usize| row.get(col).and_then(cell_to_display_string);
+
+    let mut students = Vec::new();
+
+    for row in range.rows().skip(config.header_row + 1) {
+        let raw_name = optional_cell(row, config.name).unwrap_or_default();
+        let name = normalize_name(&raw_name);
+        let id_number = row
+            .get(config.id_number)
+            .map(cell_to_id_string)
+            .unwrap_or_default();
+
+        let incomplete = id_number.is_empty();
+        if !name.is_empty() && (!incomplete || config.include_incomplete) {
+            students.push(Student {
+                name,
+                raw_name,
+                id_number: normalize_id_number(&id_number),
+                student_id: optional_cell(row, config.student_id),
+                class: optional_cell(row, config.class),
+                grade: optional_cell(row, config.grade),
+                school: optional_cell(row, config.school),
+                incomplete,
+            });
+        }
+    }
+
+    students
+}
+
+/// 每处理多少行回报一次读取进度
+const PROGRESS_INTERVAL: usize = 500;
+
+/// 读取进度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ReadProgress {
+    pub processed_rows: usize,
+    pub total_rows: usize,
+}
+
+/// 每隔 PROGRESS_INTERVAL 行以及读取完成时回报进度
+///
+/// 回调返回 false 表示请求取消, 此时返回 [`ExcelError::Cancelled`] 中止读取。
+fn report_progress(
+    on_progress: &dyn Fn(ReadProgress) -> bool,
+    processed_rows: usize,
+    total_rows: usize,
+) -> Result<(), ExcelError> {
+    if processed_rows.is_multiple_of(PROGRESS_INTERVAL) || processed_rows == total_rows {
+        let keep_going = on_progress(ReadProgress {
+            processed_rows,
+            total_rows,
+        });
+        if !keep_going {
+            return Err(ExcelError::Cancelled);
+        }
+    }
+    Ok(())
+}
+
+/// 按 (身份证号, 困难类型) 去重, 保留首次出现的记录
+pub fn dedup_difficult_people(people: Vec<DifficultPerson>) -> Vec<DifficultPerson> {
+    let mut seen = HashSet::new();
+    people
+        .into_iter()
+        .filter(|person| seen.insert((person.id_number.clone(), person.difficulty_type)))
+        .collect()
+}
+
+/// 按 (学生身份证号, 困难类型) 去重匹配结果, 保留首次出现的记录
+///
+/// 合并多个困难类型文件的结果时使用: 同一学生的不同困难类型各保留一条。
+pub fn dedup_match_results(results: Vec<MatchResult>) -> Vec<MatchResult> {
+    let mut seen = HashSet::new();
+    results
+        .into_iter()
+        .filter(|result| {
+            seen.insert((
+                result.student.id_number.clone(),
+                result.difficult_info.difficulty_type,
+            ))
+        })
+        .collect()
+}
+
+/// 两期困难名单的差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyDiff {
+    pub added: Vec<String>,   // 新名单中新增的身份证号
+    pub removed: Vec<String>, // 旧名单中被移除的身份证号
+    pub unchanged_count: usize,
+}
+
+/// 按标准化身份证号比较新旧两期困难名单, 结果按身份证号排序
+pub fn diff_difficult_people(old: &[DifficultPerson], new: &[DifficultPerson]) -> DifficultyDiff {
+    let old_ids: HashSet<&str> = old.iter().map(|p| p.id_number.as_str()).collect();
+    let new_ids: HashSet<&str> = new.iter().map(|p| p.id_number.as_str()).collect();
+
+    let mut added: Vec<String> = new_ids
+        .difference(&old_ids)
+        .map(|id| id.to_string())
+        .collect();
+    let mut removed: Vec<String> = old_ids
+        .difference(&new_ids)
+        .map(|id| id.to_string())
+        .collect();
+    added.sort();
+    removed.sort();
+
+    DifficultyDiff {
+        added,
+        removed,
+        unchanged_count: old_ids.intersection(&new_ids).count(),
+    }
+}
+
+/// 严格模式下检查数据行是否覆盖模板所需的身份证列
+///
+/// 每行的实际列数以最后一个非空单元格计; 超过半数的非空数据行不足 `required_column + 1` 列时,
+/// 认为上传的文件与困难类型不符。多列模板只要求户主身份证列, 家庭成员列允许为空。
+fn check_column_width(
+    file_path: &str,
+    range: &Range<Data>,
+    data_start_row: usize,
+    required_column: usize,
+) -> Result<(), ExcelError> {
+    let widths: Vec<usize> = range
+        .rows()
+        .skip(data_start_row)
+        .filter_map(|row| row.iter().rposition(|cell| !cell.is_empty()))
+        .map(|last| last + 1)
+        .collect();
+    let too_narrow = widths
+        .iter()
+        .filter(|&&width| width <= required_column)
+        .count();
+
+    if too_narrow * 2 > widths.len() {
+        return Err(ExcelError::parse(
+            file_path,
+            format!(
+                "列数不足: 数据行最多 {} 列, 模板要求至少 {} 列 (身份证号位于 {} 列), 请确认文件与困难类型是否对应",
+                widths.iter().max().copied().unwrap_or(0),
+                required_column + 1,
+                column_letter(required_column)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 检查身份证列时抽样的记录数
+const ID_SHAPE_SAMPLE_SIZE: usize = 100;
+
+/// 错误信息中示例值保留的字符数, 其余替换为 `*`
+const SAMPLE_VISIBLE_CHARS: usize = 1;
+
+/// 错误信息中示例值的最大长度 (含遮挡符号)
+const SAMPLE_MAX_CHARS: usize = 6;
+
+/// 遮挡错误信息中的单元格示例值: 只保留首个字符以便辨认列的内容, 并截断过长的值
+///
+/// 错误信息会写入日志, 示例值可能是姓名或身份证号, 不能原样输出。
+fn mask_sample_value(value: &str) -> String {
+    value
+        .chars()
+        .take(SAMPLE_MAX_CHARS)
+        .enumerate()
+        .map(|(i, c)| if i < SAMPLE_VISIBLE_CHARS { c } else { '*' })
+        .collect()
+}
+
+/// 抽样检查读出的身份证号, 超过半数不符合 15/18 位形态时认为列对应错误
+///
+/// 常见于选错困难类型导致身份证列实际为姓名等其他内容; 含遮挡符号的号码按形态正常处理。
+/// 错误信息中的示例值经 [`mask_sample_value`] 遮挡。
+fn check_id_column_shape(file_path: &str, people: &[DifficultPerson]) -> Result<(), ExcelError> {
+    let sample: Vec<&str> = people
+        .iter()
+        .take(ID_SHAPE_SAMPLE_SIZE)
+        .map(|person| person.raw_id_number.as_str())
+        .collect();
+    let malformed: Vec<&str> = sample
+        .iter()
+        .copied()
+        .filter(|id| id_format(&normalize_id_number(id)) == IdFormat::Other)
+        .collect();
+
+    if malformed.len() * 2 > sample.len() {
+        let examples: Vec<String> = malformed
+            .iter()
+            .take(3)
+            .map(|id| format!("\"{}\"", mask_sample_value(id)))
+            .collect();
+        return Err(ExcelError::parse(
+            file_path,
+            format!(
+                "身份证号列疑似对应错误: 抽样 {} 条中有 {} 条不是身份证号 (如 {}), 请确认困难类型或列设置是否正确",
+                sample.len(),
+                malformed.len(),
+                examples.join("、")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 一组身份证号的形态统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdFormatSummary {
+    pub sample_size: usize,               // 抽样数量
+    pub counts: HashMap<IdFormat, usize>, // 各形态的数量
+    pub dominant: Option<IdFormat>,       // 数量最多的形态, 抽样为空时为 None
+}
+
+impl IdFormatSummary {
+    /// 抽样统计身份证号形态, 最多取前 [`ID_SHAPE_SAMPLE_SIZE`] 个
+    fn from_ids<'a>(ids: impl Iterator<Item = &'a str>) -> Self {
+        let mut counts = HashMap::new();
+        let mut sample_size = 0;
+        for id in ids.take(ID_SHAPE_SAMPLE_SIZE) {
+            *counts.entry(id_format(id)).or_insert(0) += 1;
+            sample_size += 1;
+        }
+        // 数量相同时按形态顺序取第一个, 保证结果稳定
+        let dominant = [
+            IdFormat::Full18,
+            IdFormat::Legacy15,
+            IdFormat::Masked,
+            IdFormat::Other,
+        ]
+        .into_iter()
+        .filter(|format| counts.contains_key(format))
+        .max_by_key(|format| (counts[format], std::cmp::Reverse(*format as u8)));
+        Self {
+            sample_size,
+            counts,
+            dominant,
+        }
+    }
+}
+
+/// 名册与困难类型表的身份证号形态对比结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    pub student: IdFormatSummary,
+    pub difficulty: IdFormatSummary,
+    pub compatible: bool,     // 两侧主要形态一致
+    pub hint: Option<String>, // 不一致时的说明
+}
+
+/// 对比名册与困难人员的身份证号形态, 解释匹配数为 0 的常见原因
+pub fn check_id_compatibility(
+    students: &[Student],
+    difficult_people: &[DifficultPerson],
+) -> CompatibilityReport {
+    let student = IdFormatSummary::from_ids(
+        students
+            .iter()
+            .map(|student| student.id_number.as_str())
+            .filter(|id| !id.is_empty()),
+    );
+    let difficulty = IdFormatSummary::from_ids(
+        difficult_people
+            .iter()
+            .map(|person| person.id_number.as_str()),
+    );
+
+    let hint = match (student.dominant, difficulty.dominant) {
+        (Some(a), Some(b)) if a == b => None,
+        (None, _) | (_, None) => Some("名册或困难类型表中没有可用的身份证号".to_string()),
+        (Some(IdFormat::Masked), _) | (_, Some(IdFormat::Masked)) => {
+            Some("一侧身份证号被部分遮挡, 可开启遮挡匹配按未遮挡部分比对".to_string())
+        }
+        (Some(IdFormat::Legacy15), _) | (_, Some(IdFormat::Legacy15)) => {
+            Some("一侧为 15 位旧身份证号, 可开启旧号码升级后再匹配".to_string())
+        }
+        _ => Some("两侧身份证号形态不一致, 请确认列设置是否正确".to_string()),
+    };
+
+    CompatibilityReport {
+        student,
+        difficulty,
+        compatible: hint.is_none(),
+        hint,
+    }
+}
+
+/// 困难类型表读取器, 每种文件布局对应一个实现
+///
+/// 新增文件格式时实现该 trait, 并在 [`DifficultyType::reader`] 中返回对应的读取器。
+pub trait DifficultyReader {
+    /// 读取困难人员, `strict` 与 `on_progress` 的含义同 [`read_difficult_type_table_with_progress`]
+    fn read_with_progress(
+        &self,
+        file_path: &str,
+        strict: bool,
+        on_progress: &dyn Fn(ReadProgress) -> bool,
+    ) -> Result<Vec<DifficultPerson>, ExcelError>;
+
+    /// 读取困难人员 (非严格模式, 不回报进度)
+    fn read(&self, file_path: &str) -> Result<Vec<DifficultPerson>, ExcelError> {
+        self.read_with_progress(file_path, false, &|_| true)
+    }
+}
+
+impl DifficultyType {
+    /// 获取该困难类型对应的读取器
+    pub fn reader(&self) -> Box<dyn DifficultyReader> {
+        match self {
+            Self::OrphansAndFactuallyUnsupportedChildren => {
+                Box::new(OrphanReader(OrphanSheets::default()))
+            }
+            _ => match self.multi_column_config() {
+                Some(config) => Box::new(MultiColumnReader {
+                    difficulty_type: *self,
+                    config,
+                }),
+                None => Box::new(SingleColumnReader(*self)),
+            },
+        }
+    }
+}
+
+/// 常规单列模板: 第一个工作表, 每行一个身份证号
+pub struct SingleColumnReader(pub DifficultyType);
+
+impl DifficultyReader for SingleColumnReader {
+    fn read_with_progress(
+        &self,
+        file_path: &str,
+        strict: bool,
+        on_progress: &dyn Fn(ReadProgress) -> bool,
+    ) -> Result<Vec<DifficultPerson>, ExcelError> {
+        let range = open_sheet_range(file_path, 0)?;
+        parse_single_column_range(file_path, &range, self.0, strict, on_progress)
+    }
+}
+
+/// 按困难类型的单列模板解析工作表 (身份证列见 [`DifficultyType::get_column_config`])
+fn parse_single_column_range(
+    file_path: &str,
+    range: &Range<Data>,
+    difficulty_type: DifficultyType,
+    strict: bool,
+    on_progress: &dyn Fn(ReadProgress) -> bool,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let mut difficult_people = Vec::new();
+
+    // 根据困难类型确定列位置
+    let (id_col, data_start_row) = difficulty_type.get_column_config();
+
+    let data_start_row = find_data_start_row(range, data_start_row);
+    if strict {
+        check_column_width(file_path, range, data_start_row, id_col)?;
+    }
+    let name_col = find_name_column(range);
+    let total_rows = range.height().saturating_sub(data_start_row);
+    for (row_idx, row) in range.rows().skip(data_start_row).enumerate() {
+        report_progress(on_progress, row_idx + 1, total_rows)?;
+
+        let id_number = row.get(id_col).map(cell_to_id_string).unwrap_or_default();
+
+        // 只要身份证号不为空就添加记录
+        if !id_number.is_empty() {
+            let difficult_person = DifficultPerson {
+                id_number: normalize_id_number(&id_number),
+                raw_id_number: id_number.clone(),
+                difficulty_type,
+                name: read_name_cell(row, name_col),
+            };
+            difficult_people.push(difficult_person);
+        }
+    }
+    Ok(dedup_difficult_people(difficult_people))
+}
+
+/// 按索引读取多个工作表, 跳过不存在的工作表; 一个都不存在时返回错误
+fn read_existing_sheets<RS: Read + Seek>(
+    file_path: &str,
+    workbook: &mut Sheets<RS>,
+    sheet_indices: &[usize],
+) -> Result<Vec<Range<Data>>, ExcelError> {
+    let mut ranges = Vec::new();
+    for &sheet_index in sheet_indices {
+        if let Some(range) = workbook.worksheet_range_at(sheet_index) {
+            ranges.push(range.map_err(|e| ExcelError::read(file_path, Some(sheet_index), e))?);
+        }
+    }
+
+    if ranges.is_empty() {
+        return Err(ExcelError::read(file_path, None, MISSING_SHEETS));
+    }
+    Ok(ranges)
+}
+
+/// 孤儿名册工作表名称中的关键字
+const ORPHAN_SHEET_KEYWORDS: &[&str] = &["孤儿", "事实无人抚养"];
+
+/// 孤儿名册的工作表选择方式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrphanSheets {
+    /// 按索引读取, 不存在的工作表跳过
+    Indices(Vec<usize>),
+    /// 读取名称包含 "孤儿" 或 "事实无人抚养" 的全部工作表
+    ByName,
+}
+
+impl Default for OrphanSheets {
+    /// 孤儿名册默认分布在第 1 和第 3 个工作表
+    fn default() -> Self {
+        Self::Indices(vec![0, 2])
+    }
+}
+
+/// 按选择方式读取孤儿名册的工作表
+fn read_orphan_sheets<RS: Read + Seek>(
+    file_path: &str,
+    workbook: &mut Sheets<RS>,
+    sheets: &OrphanSheets,
+) -> Result<Vec<Range<Data>>, ExcelError> {
+    match sheets {
+        OrphanSheets::Indices(sheet_indices) => {
+            read_existing_sheets(file_path, workbook, sheet_indices)
+        }
+        OrphanSheets::ByName => {
+            let sheet_indices: Vec<usize> = workbook
+                .sheet_names()
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| ORPHAN_SHEET_KEYWORDS.iter().any(|k| name.contains(k)))
+                .map(|(index, _)| index)
+                .collect();
+            read_existing_sheets(file_path, workbook, &sheet_indices)
+        }
+    }
+}
+
+/// 孤儿名册: 按工作表选择方式读取多个工作表
+pub struct OrphanReader(pub OrphanSheets);
+
+impl DifficultyReader for OrphanReader {
+    fn read_with_progress(
+        &self,
+        file_path: &str,
+        strict: bool,
+        on_progress: &dyn Fn(ReadProgress) -> bool,
+    ) -> Result<Vec<DifficultPerson>, ExcelError> {
+        read_orphans_with_sheets(file_path, &self.0, strict, on_progress)
+    }
+}
+
+/// 按指定的工作表读取孤儿名册, 多个工作表间按身份证号去重
+pub fn read_orphans_with_sheets(
+    file_path: &str,
+    sheets: &OrphanSheets,
+    strict: bool,
+    on_progress: &dyn Fn(ReadProgress) -> bool,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let mut difficult_people = Vec::new();
+    let (id_col, default_start_row) =
+        DifficultyType::OrphansAndFactuallyUnsupportedChildren.get_column_config();
+
+    let mut workbook = open_spreadsheet(file_path)?;
+    let ranges = read_orphan_sheets(file_path, &mut workbook, sheets)?;
+    let ranges: Vec<(Range<Data>, usize, Option<usize>)> = ranges
+        .into_iter()
+        .map(|range| {
+            let data_start_row = find_data_start_row(&range, default_start_row);
+            let name_col = find_name_column(&range);
+            (range, data_start_row, name_col)
+        })
+        .collect();
+
+    let total_rows = ranges
+        .iter()
+        .map(|(range, data_start_row, _)| range.height().saturating_sub(*data_start_row))
+        .sum();
+    let mut processed_rows = 0;
+
+    if strict {
+        for (range, data_start_row, _) in &ranges {
+            check_column_width(file_path, range, *data_start_row, id_col)?;
+        }
+    }
+
+    for (range, data_start_row, name_col) in &ranges {
+        for row in range.rows().skip(*data_start_row) {
+            processed_rows += 1;
+            report_progress(on_progress, processed_rows, total_rows)?;
+
+            let id_number = row.get(id_col).map(cell_to_id_string).unwrap_or_default();
+
+            // 只要身份证号不为空就添加记录
+            if !id_number.is_empty() {
+                let difficult_person = DifficultPerson {
+                    id_number: normalize_id_number(&id_number),
+                    raw_id_number: id_number.clone(),
+                    difficulty_type: DifficultyType::OrphansAndFactuallyUnsupportedChildren,
+                    name: read_name_cell(row, *name_col),
+                };
+                difficult_people.push(difficult_person);
+            }
+        }
+    }
+    Ok(dedup_difficult_people(difficult_people))
+}
+
+/// 提取一行中指定列的所有非空身份证号
+fn row_id_numbers(row: &[Data], id_columns: &[usize]) -> Vec<String> {
+    id_columns
+        .iter()
+        .filter_map(|&col| row.get(col))
+        .map(cell_to_id_string)
+        .filter(|id_number| !id_number.is_empty())
+        .collect()
+}
+
+/// 逐行提取指定列的所有非空身份证号
+pub fn extract_id_columns_sequential(rows: &[&[Data]], id_columns: &[usize]) -> Vec<String> {
+    rows.iter()
+        .flat_map(|row| row_id_numbers(row, id_columns))
+        .collect()
+}
+
+/// 并行提取指定列的所有非空身份证号, 结果顺序与逐行提取一致
+#[cfg(feature = "parallel")]
+pub fn extract_id_columns_parallel(rows: &[&[Data]], id_columns: &[usize]) -> Vec<String> {
+    use rayon::prelude::*;
+
+    rows.par_iter()
+        .flat_map_iter(|row| row_id_numbers(row, id_columns))
+        .collect()
+}
+
+/// 提取指定列的所有非空身份证号, 启用 `parallel` 特性时并行处理
+fn extract_id_columns(rows: &[&[Data]], id_columns: &[usize]) -> Vec<String> {
+    #[cfg(feature = "parallel")]
+    return extract_id_columns_parallel(rows, id_columns);
+
+    #[cfg(not(feature = "parallel"))]
+    extract_id_columns_sequential(rows, id_columns)
+}
+
+/// 多列身份证号模板配置 (列索引从 0 开始)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiColumnConfig {
+    pub sheet_index: usize,     // 工作表索引
+    pub skip_rows: usize,       // 未识别到表头时跳过的行数
+    pub id_columns: Vec<usize>, // 户主及家庭成员身份证列
+}
+
+/// 按多列模板读取困难人员, 每行可包含多个身份证号
+pub fn read_multi_column(
+    file_path: &str,
+    config: &MultiColumnConfig,
+    difficulty_type: DifficultyType,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let people =
+        read_multi_column_with_progress(file_path, config, difficulty_type, false, &|_| true)?;
+    check_id_column_shape(file_path, &people)?;
+    Ok(people)
+}
+
+/// 按多列模板读取困难人员, 并在读取过程中定期回报进度
+///
+/// 不检查身份证列的形态, 由调用方 (如 [`read_difficult_type_table_with_progress`]) 统一检查
+pub fn read_multi_column_with_progress(
+    file_path: &str,
+    config: &MultiColumnConfig,
+    difficulty_type: DifficultyType,
+    strict: bool,
+    on_progress: &dyn Fn(ReadProgress) -> bool,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let range = open_sheet_range(file_path, config.sheet_index)?;
+    parse_multi_column_range(
+        file_path,
+        &range,
+        config,
+        difficulty_type,
+        strict,
+        on_progress,
+    )
+}
+
+/// 读取没有固定模板的身份证号清单 (第一个工作表, 每行一个身份证号)
+///
+/// `id_column` 为身份证号所在列 (从 0 开始), `skip_rows` 为表头占用的行数;
+/// 表头中有 "姓名" 列时一并读取姓名, 供按姓名的第二轮匹配使用
+pub fn read_simple_id_list(
+    file_path: &str,
+    id_column: usize,
+    skip_rows: usize,
+    difficulty_type: DifficultyType,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let range = open_sheet_range(file_path, 0)?;
+    let name_col = find_name_column(&range);
+    let difficult_people = range
+        .rows()
+        .skip(skip_rows)
+        .filter_map(|row| {
+            let id_number = row.get(id_column).map(cell_to_id_string)?;
+            if id_number.is_empty() {
+                return None;
+            }
+            Some(DifficultPerson {
+                id_number: normalize_id_number(&id_number),
+                raw_id_number: id_number,
+                difficulty_type,
+                name: read_name_cell(row, name_col),
+            })
+        })
+        .collect();
+    let people = dedup_difficult_people(difficult_people);
+    check_id_column_shape(file_path, &people)?;
+    Ok(people)
+}
+
+/// 按多列模板解析工作表 (忽略配置中的工作表索引)
+fn parse_multi_column_range(
+    file_path: &str,
+    range: &Range<Data>,
+    config: &MultiColumnConfig,
+    difficulty_type: DifficultyType,
+    strict: bool,
+    on_progress: &dyn Fn(ReadProgress) -> bool,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let mut difficult_people = Vec::new();
+
+    let data_start_row = find_data_start_row(range, config.skip_rows);
+    if let Some(&head_col) = config.id_columns.first().filter(|_| strict) {
+        check_column_width(file_path, range, data_start_row, head_col)?;
+    }
+    let rows: Vec<&[Data]> = range.rows().skip(data_start_row).collect();
+    let total_rows = rows.len();
+
+    // 姓名列只对应户主, 家庭成员的身份证号不带姓名
+    let head_names: HashMap<String, String> =
+        match (find_name_column(range), config.id_columns.first()) {
+            (Some(name_col), Some(&head_col)) => rows
+                .iter()
+                .filter_map(|row| {
+                    let id_number = row.get(head_col).map(cell_to_id_string)?;
+                    Some((id_number, read_name_cell(row, Some(name_col))?))
+                })
+                .collect(),
+            _ => HashMap::new(),
+        };
+
+    // 按进度回报间隔分块扫描, 块内可并行, 结果保持原有行列顺序
+    for (chunk_idx, chunk) in rows.chunks(PROGRESS_INTERVAL).enumerate() {
+        for id_number in extract_id_columns(chunk, &config.id_columns) {
+            let difficult_person = DifficultPerson {
+                id_number: normalize_id_number(&id_number),
+                name: head_names.get(&id_number).cloned(),
+                raw_id_number: id_number,
+                difficulty_type,
+            };
+            difficult_people.push(difficult_person);
+        }
+        report_progress(
+            on_progress,
+            chunk_idx * PROGRESS_INTERVAL + chunk.len(),
+            total_rows,
+        )?;
+    }
+    Ok(dedup_difficult_people(difficult_people))
+}
+
+/// 多列模板 (农村低保、城镇低保、城乡特困): 每行包含户主及家庭成员的身份证号
+pub struct MultiColumnReader {
+    pub difficulty_type: DifficultyType,
+    pub config: MultiColumnConfig,
+}
+
+impl DifficultyReader for MultiColumnReader {
+    fn read_with_progress(
+        &self,
+        file_path: &str,
+        strict: bool,
+        on_progress: &dyn Fn(ReadProgress) -> bool,
+    ) -> Result<Vec<DifficultPerson>, ExcelError> {
+        read_multi_column_with_progress(
+            file_path,
+            &self.config,
+            self.difficulty_type,
+            strict,
+            on_progress,
+        )
+    }
+}
+
+/// 自检时单个身份证列的读取情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnSelfTest {
+    pub sheet_index: usize,
+    pub column: usize,
+    pub column_letter: String,
+    pub non_empty_count: usize, // 非空单元格数
+    pub id_count: usize,        // 其中形态为身份证号的数量
+}
+
+/// 困难类型文件自检报告: 按模板配置逐列统计读出的身份证号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub difficulty_type: DifficultyType,
+    pub columns: Vec<ColumnSelfTest>,
+    pub empty_columns: Vec<String>, // 没有读出身份证号的列, 如 "工作表 2 的 Q 列", 多为模板列发生偏移
+    pub total_ids: usize,           // 去重后的身份证号数量
+}
+
+/// 按困难类型的模板配置自检文件, 统计每个身份证列读出的号码数量
+///
+/// 不存在的工作表跳过 (孤儿名册允许缺少部分工作表); 配置中的工作表都不存在时返回错误。
+pub fn selftest_difficulty_file(
+    file_path: &str,
+    difficulty_type: DifficultyType,
+) -> Result<SelfTestReport, ExcelError> {
+    let layout = difficulty_type.layout();
+    let mut columns = Vec::new();
+    let mut ids = HashSet::new();
+
+    for &sheet_index in &layout.sheet_indices {
+        let range = match open_sheet_range(file_path, sheet_index) {
+            Ok(range) => range,
+            Err(ExcelError::SheetNotFound { .. }) => continue,
+            Err(e) => return Err(e),
+        };
+        let data_start_row = find_data_start_row(&range, layout.data_start_row);
+        for &column in &layout.id_columns {
+            let mut non_empty_count = 0;
+            let mut id_count = 0;
+            for row in range.rows().skip(data_start_row) {
+                let raw = row.get(column).map(cell_to_id_string).unwrap_or_default();
+                if raw.is_empty() {
+                    continue;
+                }
+                non_empty_count += 1;
+                let id_number = normalize_id_number(&raw);
+                if id_format(&id_number) != IdFormat::Other {
+                    id_count += 1;
+                    ids.insert(id_number);
+                }
+            }
+            columns.push(ColumnSelfTest {
+                sheet_index,
+                column,
+                column_letter: column_letter(column),
+                non_empty_count,
+                id_count,
+            });
+        }
+    }
+
+    if columns.is_empty() {
+        return Err(ExcelError::read(file_path, None, MISSING_SHEETS));
+    }
+
+    let empty_columns = columns
+        .iter()
+        .filter(|column| column.id_count == 0)
+        .map(|column| {
+            format!(
+                "工作表 {} 的 {} 列",
+                column.sheet_index + 1,
+                column.column_letter
+            )
+        })
+        .collect();
+
+    Ok(SelfTestReport {
+        difficulty_type,
+        columns,
+        empty_columns,
+        total_ids: ids.len(),
+    })
+}
+
+/// 按工作表名称读取困难类型表, 适用于多个类别合并在同一工作簿的文件
+///
+/// 多列模板的类型按 [`DifficultyType::multi_column_config`] 解析, 其余按单列模板解析。
+pub fn read_difficult_type_by_sheet_name(
+    file_path: &str,
+    sheet_name: &str,
+    difficulty_type: DifficultyType,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    let range = open_sheet_range_by_name(file_path, sheet_name)?;
+    let people = match difficulty_type.multi_column_config() {
+        Some(config) => {
+            parse_multi_column_range(file_path, &range, &config, difficulty_type, false, &|_| {
+                true
+            })?
+        }
+        None => parse_single_column_range(file_path, &range, difficulty_type, false, &|_| true)?,
+    };
+    check_id_column_shape(file_path, &people)?;
+    Ok(people)
+}
+
+/// 困难类型表预检结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DifficultyFileReport {
+    pub sheet_indices: Vec<usize>, // 模板读取的工作表
+    pub sheet_count: usize,        // 文件中的工作表数量
+    pub sheet_found: bool,         // 模板工作表至少有一个存在 (孤儿名册允许缺少部分工作表)
+    pub id_columns: Vec<usize>,    // 身份证号所在列 (多列模板含家庭成员列)
+    pub data_rows: usize,          // 数据行数
+    pub non_empty_ids: usize,      // 身份证列非空单元格数
+    pub id_shaped_count: usize,    // 疑似身份证号数量
+}
+
+/// 按困难类型的模板布局预检文件, 不做完整读取
+///
+/// 工作表、数据开始行与身份证列均取自 [`DifficultyType::layout`], 与实际读取时一致。
+pub fn validate_difficulty_file(
+    file_path: &str,
+    difficulty_type: DifficultyType,
+) -> Result<DifficultyFileReport, ExcelError> {
+    let sheet_count = read_worksheet_infos(file_path)?.len();
+    let layout = difficulty_type.layout();
+
+    let mut report = DifficultyFileReport {
+        sheet_found: layout
+            .sheet_indices
+            .iter()
+            .any(|&index| index < sheet_count),
+        sheet_indices: layout.sheet_indices,
+        sheet_count,
+        id_columns: layout.id_columns,
+        data_rows: 0,
+        non_empty_ids: 0,
+        id_shaped_count: 0,
+    };
+
+    for &sheet_index in report
+        .sheet_indices
+        .iter()
+        .filter(|&&index| index < sheet_count)
+    {
+        let range = open_sheet_range(file_path, sheet_index)?;
+        let data_start_row = find_data_start_row(&range, layout.data_start_row);
+        for row in range.rows().skip(data_start_row) {
+            report.data_rows += 1;
+            for id_number in row_id_numbers(row, &report.id_columns) {
+                report.non_empty_ids += 1;
+                if looks_like_id_number(&normalize_id_number(&id_number)) {
+                    report.id_shaped_count += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// 读取困难类型表
+pub fn read_difficult_type_table(
+    file_path: &str,
+    difficulty_type: DifficultyType,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    read_difficult_type_table_with_progress(file_path, difficulty_type, false, &|_| true)
+}
+
+/// 读取困难类型表, 并在读取过程中定期回报进度
+pub fn read_difficult_type_table_with_progress(
+    file_path: &str,
+    difficulty_type: DifficultyType,
+    strict: bool,
+    on_progress: &dyn Fn(ReadProgress) -> bool,
+) -> Result<Vec<DifficultPerson>, ExcelError> {
+    if !Path::new(file_path).exists() {
+        return Err(ExcelError::FileNotFound(file_path.to_string()));
+    }
+
+    let people = difficulty_type
+        .reader()
+        .read_with_progress(file_path, strict, on_progress)?;
+    check_id_column_shape(file_path, &people)?;
+    Ok(people)
+}
+
+/// 按身份证号查找名册中的重复学生, 按首次出现顺序返回记录数大于 1 的分组 (资料不全的记录除外)
+pub fn find_duplicate_students(students: &[Student]) -> Vec<(String, Vec<Student>)> {
+    let mut groups: Vec<(String, Vec<Student>)> = Vec::new();
+    let mut index_by_id: HashMap<&str, usize> = HashMap::new();
+
+    for student in students.iter().filter(|student| !student.incomplete) {
+        match index_by_id.get(student.id_number.as_str()) {
+            Some(&index) => groups[index].1.push(student.clone()),
+            None => {
+                index_by_id.insert(&student.id_number, groups.len());
+                groups.push((student.id_number.clone(), vec![student.clone()]));
+            }
+        }
+    }
+
+    groups.retain(|(_, group)| group.len() > 1);
+    groups
+}
+
+/// 完整匹配结果 (包含未匹配到学生的困难人员)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatchOutcome {
+    pub matched: Vec<MatchResult>,
+    pub unmatched: Vec<DifficultPerson>,
+}
+
+/// 匹配学生信息和困难类型信息
+pub fn match_students_with_difficulty(
+    students: &[Student],
+    difficult_people: &[DifficultPerson],
+) -> Vec<MatchResult> {
+    match_students_with_difficulty_full(students, difficult_people).matched
+}
+
+/// 统计身份证号同时出现在名册和困难人员中的学生数, 不生成匹配记录
+pub fn count_matching_ids(students: &[Student], difficult_people: &[DifficultPerson]) -> usize {
+    let difficult_ids: HashSet<&str> = difficult_people
+        .iter()
+        .map(|person| person.id_number.as_str())
+        .filter(|id| !id.is_empty())
+        .collect();
+    students
+        .iter()
+        .map(|student| student.id_number.as_str())
+        .filter(|id| difficult_ids.contains(id))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// 匹配学生信息和困难类型信息, 同时返回未匹配到学生的困难人员
+///
+/// 名册中同一身份证号出现多次时以首次出现的记录为准, 重复记录可用
+/// [`find_duplicate_students`] 提前检查。
+pub fn match_students_with_difficulty_full(
+    students: &[Student],
+    difficult_people: &[DifficultPerson],
+) -> MatchOutcome {
+    let mut outcome = MatchOutcome::default();
+
+    // 创建学生身份证号的哈希映射以提高查询效率
+    // 空身份证号不参与匹配, 避免两侧的空白单元格互相匹配
+    let mut student_map: HashMap<&str, &Student> = HashMap::new();
+    for student in students
+        .iter()
+        .filter(|student| !student.incomplete && !student.id_number.is_empty())
+    {
+        student_map.entry(&student.id_number).or_insert(student);
+    }
+
+    for difficult_person in difficult_people {
+        let student = Some(difficult_person.id_number.as_str())
+            .filter(|id| !id.is_empty())
+            .and_then(|id| student_map.get(id));
+        if let Some(student) = student {
+            outcome.matched.push(MatchResult {
+                student: (*student).clone(),
+                difficult_info: difficult_person.clone(),
+                match_kind: MatchKind::ExactId,
+            });
+        } else {
+            outcome.unmatched.push(difficult_person.clone());
+        }
+    }
+
+    outcome
+}
+
+/// 按选项匹配学生信息和困难类型信息
+///
+/// 开启 `fuzzy` 时, 对身份证号未匹配的困难人员按姓名进行第二轮匹配:
+/// 仅当名册中恰有一名同名学生时才视为匹配, 结果标记为 [`MatchKind::ByName`]。
+pub fn match_students_with_difficulty_opts(
+    students: &[Student],
+    difficult_people: &[DifficultPerson],
+    options: &MatchOptions,
+) -> Vec<MatchResult> {
+    let canonical_students: Vec<Student>;
+    let canonical_people: Vec<DifficultPerson>;
+    let (students, difficult_people) = if options.legacy_ids {
+        canonical_students = students
+            .iter()
+            .map(|student| Student {
+                id_number: canonicalize_id(&student.id_number),
+                ..student.clone()
+            })
+            .collect();
+        canonical_people = difficult_people
+            .iter()
+            .map(|person| DifficultPerson {
+                id_number: canonicalize_id(&person.id_number),
+                ..person.clone()
+            })
+            .collect();
+        (canonical_students.as_slice(), canonical_people.as_slice())
+    } else {
+        (students, difficult_people)
+    };
+
+    let outcome = match_students_with_difficulty_full(students, difficult_people);
+    let mut results = outcome.matched;
+
+    // 已匹配的 (学生, 困难类型) 不再重复匹配
+    let mut matched: HashSet<(String, DifficultyType)> = results
+        .iter()
+        .map(|m| {
+            (
+                m.student.id_number.clone(),
+                m.difficult_info.difficulty_type,
+            )
+        })
+        .collect();
+
+    if options.masked {
+        results.extend(match_masked_students(
+            students,
+            difficult_people,
+            &mut matched,
+        ));
+    }
+
+    if options.fuzzy {
+        let mut students_by_name: HashMap<&str, Vec<&Student>> = HashMap::new();
+        for student in students.iter().filter(|student| !student.incomplete) {
+            students_by_name
+                .entry(student.name.trim())
+                .or_default()
+                .push(student);
+        }
+
+        for difficult_person in &outcome.unmatched {
+            let Some(name) = difficult_person.name.as_deref() else {
+                continue;
+            };
+            // 困难人员可能来自前端传入的数据, 查找前同样规范化姓名
+            let name = normalize_name(name);
+            if let Some([student]) = students_by_name.get(name.as_str()).map(Vec::as_slice)
+                && matched.insert((student.id_number.clone(), difficult_person.difficulty_type))
+            {
+                results.push(MatchResult {
+                    student: (*student).clone(),
+                    difficult_info: difficult_person.clone(),
+                    match_kind: MatchKind::ByName,
+                });
+            }
+        }
+    }
+
+    results
+}
+
+/// 判断遮挡的身份证号与完整身份证号的可见部分是否一致
+///
+/// 遮挡字符为 `*` 或全角 `＊`, 两者长度须相同且至少有一位被遮挡; 未遮挡的号码交给精确匹配。
+pub fn masked_id_matches(masked: &str, full: &str) -> bool {
+    let mut has_mask = false;
+    let mut masked = masked.chars();
+    let mut full = full.chars();
+    loop {
+        match (masked.next(), full.next()) {
+            (Some(m), Some(_)) if is_id_mask(m) => has_mask = true,
+            (Some(m), Some(f)) if m != f => return false,
+            (Some(_), Some(_)) => {}
+            (None, None) => return has_mask,
+            _ => return false,
+        }
+    }
+}
+
+/// 遮挡匹配索引使用的可见前缀 (地区码) 与后缀长度
+const MASK_KEY_PREFIX_LEN: usize = 6;
+const MASK_KEY_SUFFIX_LEN: usize = 4;
+
+/// 遮挡匹配的索引键: (长度, 前 6 位, 后 4 位), 前后缀中有遮挡字符时为 None
+fn masked_index_key(id: &str) -> Option<(usize, String, String)> {
+    let len = id.chars().count();
+    if len < MASK_KEY_PREFIX_LEN + MASK_KEY_SUFFIX_LEN {
+        return None;
+    }
+    let prefix: String = id.chars().take(MASK_KEY_PREFIX_LEN).collect();
+    let suffix: String = id.chars().skip(len - MASK_KEY_SUFFIX_LEN).collect();
+    if prefix.chars().chain(suffix.chars()).any(is_id_mask) {
+        return None;
+    }
+    Some((len, prefix, suffix))
+}
+
+/// 按年级、班级、学校筛选匹配结果, 多个条件同时满足才保留
+///
+/// 比较时忽略首尾空白和大小写; 条件为空时不限制该字段。
+pub fn filter_match_results(
+    matches: Vec<MatchResult>,
+    grade: Option<&str>,
+    class: Option<&str>,
+    school: Option<&str>,
+) -> Vec<MatchResult> {
+    let normalize = |value: &str| value.trim().to_lowercase();
+    let wanted = |value: Option<&str>| value.map(normalize).filter(|v| !v.is_empty());
+    let (grade, class, school) = (wanted(grade), wanted(class), wanted(school));
+    let field_matches = |value: &Option<String>, wanted: &Option<String>| match wanted {
+        Some(wanted) => value.as_deref().is_some_and(|v| normalize(v) == *wanted),
+        None => true,
+    };
+
+    matches
+        .into_iter()
+        .filter(|m| {
+            field_matches(&m.student.grade, &grade)
+                && field_matches(&m.student.class, &class)
+                && field_matches(&m.student.school, &school)
+        })
+        .collect()
+}
+
+/// 匹配结果的排序字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    School,
+    Grade,
+    Class,
+    Name,
+    DifficultyType,
+}
+
+impl SortKey {
+    /// 按该字段比较两条结果, 缺失的字段排在最后
+    ///
+    /// `type_ranks` 为困难类型在 [`DifficultyType::all`] 中的位置, 由调用方在排序前计算一次。
+    fn compare(
+        self,
+        a: &MatchResult,
+        b: &MatchResult,
+        type_ranks: &HashMap<DifficultyType, usize>,
+    ) -> std::cmp::Ordering {
+        let optional =
+            |a: &Option<String>, b: &Option<String>, cmp: fn(&str, &str) -> _| match (a, b) {
+                (Some(a), Some(b)) => cmp(a, b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            };
+        let type_rank = |m: &MatchResult| type_ranks.get(&m.difficult_info.difficulty_type);
+
+        match self {
+            Self::School => optional(&a.student.school, &b.student.school, str::cmp),
+            Self::Grade => optional(&a.student.grade, &b.student.grade, natural_cmp),
+            Self::Class => optional(&a.student.class, &b.student.class, natural_cmp),
+            Self::Name => a.student.name.cmp(&b.student.name),
+            Self::DifficultyType => type_rank(a).cmp(&type_rank(b)),
+        }
+    }
+}
+
+/// 中文数字字符的值, `十` 记为 10
+fn chinese_digit(c: char) -> Option<u64> {
+    "零一二三四五六七八九十"
+        .chars()
+        .position(|digit| digit == c)
+        .map(|value| value as u64)
+        .or((c == '〇').then_some(0))
+}
+
+/// 解析一段连续的中文数字, 支持 "三"、"十二"、"二十"、"二〇二四" 等写法
+fn chinese_number_value(digits: &[u64]) -> u64 {
+    match digits.iter().position(|&digit| digit == 10) {
+        Some(ten) => {
+            let tens = digits[..ten].iter().fold(0, |acc, &d| acc * 10 + d).max(1);
+            let units = digits[ten + 1..].iter().fold(0, |acc, &d| acc * 10 + d);
+            tens * 10 + units
+        }
+        None => digits.iter().fold(0, |acc, &d| acc * 10 + d),
+    }
+}
+
+/// 自然排序中的一个片段: 连续的数字 (阿拉伯或中文) 按数值比较, 其余字符逐个比较
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum NaturalSegment {
+    Number(u64),
+    Char(char),
+}
+
+fn natural_segments(text: &str) -> Vec<NaturalSegment> {
+    let mut segments = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(digit) = c.to_digit(10) {
+            let mut value = u64::from(digit);
+            while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                value = value.saturating_mul(10).saturating_add(u64::from(digit));
+                chars.next();
+            }
+            segments.push(NaturalSegment::Number(value));
+        } else if let Some(digit) = chinese_digit(c) {
+            let mut digits = vec![digit];
+            while let Some(digit) = chars.peek().and_then(|&c| chinese_digit(c)) {
+                digits.push(digit);
+                chars.next();
+            }
+            segments.push(NaturalSegment::Number(chinese_number_value(&digits)));
+        } else {
+            segments.push(NaturalSegment::Char(c));
+        }
+    }
+    segments
+}
+
+/// 按自然顺序比较年级、班级等名称, 如 "一年级" < "二年级", "2班" < "10班"
+///
+/// 数值相同的写法 (如 "1班" 与 "一班") 再按原文比较, 保证排序结果确定。
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    natural_segments(a)
+        .cmp(&natural_segments(b))
+        .then_with(|| a.cmp(b))
+}
+
+/// 按多个字段依次排序 (稳定排序, 字段全部相同时保持原有顺序)
+pub fn sort_match_results(matches: &mut [MatchResult], keys: &[SortKey]) {
+    if keys.is_empty() {
+        return;
+    }
+    let type_ranks: HashMap<DifficultyType, usize> = DifficultyType::all()
+        .into_iter()
+        .enumerate()
+        .map(|(rank, difficulty_type)| (difficulty_type, rank))
+        .collect();
+    matches.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| key.compare(a, b, &type_ranks))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// 遮挡身份证号用于对外分享: 保留前 6 位和后 4 位, 其余替换为 `*`
+///
+/// 不足 11 位的号码无法保留首尾, 全部替换。
+pub fn mask_id_number(id: &str) -> String {
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() <= 10 {
+        return "*".repeat(chars.len());
+    }
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            if i < 6 || i >= chars.len() - 4 {
+                c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+/// 返回身份证号均已遮挡的匹配结果副本
+pub fn mask_match_ids(matches: &[MatchResult]) -> Vec<MatchResult> {
+    matches
+        .iter()
+        .map(|m| {
+            let mut m = m.clone();
+            m.student.id_number = mask_id_number(&m.student.id_number);
+            m.difficult_info.id_number = mask_id_number(&m.difficult_info.id_number);
+            m.difficult_info.raw_id_number = mask_id_number(&m.difficult_info.raw_id_number);
+            m
+        })
+        .collect()
+}
+
+/// 遮挡身份证号的匹配: 只有可见部分唯一对应一个身份证号时才匹配
+///
+/// 多名学生的遮挡号码对应同一个身份证号时无法确定是谁, 这些学生都不匹配。
+fn match_masked_students(
+    students: &[Student],
+    difficult_people: &[DifficultPerson],
+    matched: &mut HashSet<(String, DifficultyType)>,
+) -> Vec<MatchResult> {
+    let mut results = Vec::new();
+
+    // 按可见的前后缀为完整身份证号建立索引, 常见的 "110101********002X" 只需比较同组号码
+    let mut people_by_key: HashMap<(usize, String, String), Vec<&DifficultPerson>> = HashMap::new();
+    for person in difficult_people {
+        if let Some(key) = masked_index_key(&person.id_number) {
+            people_by_key.entry(key).or_default().push(person);
+        }
+    }
+
+    // 先确定每名学生唯一对应的身份证号, 再排除多人对应同一号码的情况
+    let mut resolved: Vec<(&Student, Vec<&DifficultPerson>)> = Vec::new();
+    let mut students_per_id: HashMap<&str, usize> = HashMap::new();
+    let masked_students = students
+        .iter()
+        .filter(|student| student.id_number.chars().any(is_id_mask));
+    for student in masked_students {
+        // 前后缀也被遮挡时无法使用索引, 逐个比较
+        let candidates: Vec<&DifficultPerson> = match masked_index_key(&student.id_number) {
+            Some(key) => people_by_key.get(&key).map_or_else(Vec::new, |group| {
+                group
+                    .iter()
+                    .copied()
+                    .filter(|person| masked_id_matches(&student.id_number, &person.id_number))
+                    .collect()
+            }),
+            None => difficult_people
+                .iter()
+                .filter(|person| masked_id_matches(&student.id_number, &person.id_number))
+                .collect(),
+        };
+        let mut ids = candidates.iter().map(|p| p.id_number.as_str());
+        let Some(id_number) = ids.next() else {
+            continue;
+        };
+        if ids.any(|other| other != id_number) {
+            continue;
+        }
+        *students_per_id.entry(id_number).or_default() += 1;
+        resolved.push((student, candidates));
+    }
+
+    for (student, candidates) in resolved {
+        if students_per_id[candidates[0].id_number.as_str()] > 1 {
+            continue;
+        }
+        for person in candidates {
+            if matched.insert((student.id_number.clone(), person.difficulty_type)) {
+                results.push(MatchResult {
+                    student: student.clone(),
+                    difficult_info: person.clone(),
+                    match_kind: MatchKind::Masked,
+                });
+            }
+        }
+    }
+
+    results
+}
+
+/// 匿名化映射 (同一次运行内相同的原值映射到相同的替换值)
+#[derive(Debug, Default)]
+struct Anonymizer {
+    ids: HashMap<String, String>,
+    names: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    /// 生成一个保持长度且校验位合法的虚构身份证号
+    fn anonymize_id(&mut self, id: &str) -> String {
+        let next = self.ids.len();
+        self.ids
+            .entry(id.to_string())
+            .or_insert_with(|| synthetic_id_number(next, id.len()))
+            .clone()
+    }
+
+    /// 将姓名替换为占位符
+    fn anonymize_name(&mut self, name: &str) -> String {
+        let next = self.names.len() + 1;
+        self.names
+            .entry(name.to_string())
+            .or_insert_with(|| format!("学生{}", next))
+            .clone()
+    }
+}
+
+/// 按序号生成虚构身份证号: 地区码固定, 出生日期和顺序码由序号推算
+fn synthetic_id_number(seq: usize, len: usize) -> String {
+    let base = NaiveDate::from_ymd_opt(2010, 1, 1).expect("valid base date");
+    let birth_date = base + Duration::days((seq / 1000) as i64);
+    let order = seq % 1000;
+
+    if len == 15 {
+        format!("110101{}{:03}", birth_date.format("%y%m%d"), order)
+    } else {
+        let body = format!("110101{}{:03}", birth_date.format("%Y%m%d"), order);
+        let check = id_check_digit(&body).expect("body is 17 digits");
+        format!("{}{}", body, check)
+    }
+}
+
+/// 生成匿名化样例文件
+///
+/// 读取指定工作表, 将所有形似身份证号的单元格替换为虚构但合法的身份证号,
+/// 并将表头含"姓名"的列替换为占位符, 其余单元格原样保留, 便于复现解析问题。
+/// 输出文件始终为 xlsx 格式。
+pub fn anonymize_sheet(
+    input_path: &str,
+    output_path: &str,
+    sheet_index: usize,
+) -> Result<(), ExcelError> {
+    let range = open_sheet_range(input_path, sheet_index)?;
+    let (start_row, start_col) = range.start().unwrap_or((0, 0));
+
+    // 在前几行中查找"姓名"表头, 确定姓名列及其表头所在行
+    let mut name_columns: HashMap<usize, usize> = HashMap::new();
+    for (row_idx, row) in range.rows().take(10).enumerate() {
+        for (col_idx, cell) in row.iter().enumerate() {
+            if cell.as_string().is_some_and(|v| v.contains("姓名")) {
+                name_columns.entry(col_idx).or_insert(row_idx);
+            }
+        }
+    }
+
+    let mut anonymizer = Anonymizer::default();
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    // 日期单元格保留日期格式, 否则重新读取时只得到序列号
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+    let duration_format = Format::new().set_num_format("[h]:mm:ss");
+
+    for (row_idx, col_idx, cell) in range.used_cells() {
+        let row = start_row + row_idx as u32;
+        let col = (start_col as usize + col_idx) as u16;
+
+        let is_name_cell = name_columns
+            .get(&col_idx)
+            .is_some_and(|&header_row| row_idx > header_row);
+
+        let result = match cell {
+            Data::String(value) => {
+                let normalized = normalize_id_number(value);
+                if looks_like_id_number(&normalized) {
+                    worksheet.write_string(row, col, anonymizer.anonymize_id(&normalized))
+                } else if is_name_cell && !value.trim().is_empty() {
+                    worksheet.write_string(row, col, anonymizer.anonymize_name(value.trim()))
+                } else {
+                    worksheet.write_string(row, col, value)
+                }
+            }
+            Data::Float(_) | Data::Int(_) => {
+                let value = cell_to_id_string(cell);
+                if looks_like_id_number(&value) {
+                    worksheet.write_string(row, col, anonymizer.anonymize_id(&value))
+                } else {
+                    worksheet.write_number(row, col, cell.as_f64().unwrap_or_default())
+                }
+            }
+            Data::Bool(value) => worksheet.write_boolean(row, col, *value),
+            Data::DateTime(value) => {
+                let serial = value.as_f64();
+                let format = if value.is_duration() {
+                    &duration_format
+                } else if serial.fract() == 0.0 {
+                    &date_format
+                } else {
+                    &datetime_format
+                };
+                worksheet.write_number_with_format(row, col, serial, format)
+            }
+            Data::DateTimeIso(value) | Data::DurationIso(value) => {
+                worksheet.write_string(row, col, value)
+            }
+            Data::Error(_) | Data::Empty => continue,
+        };
+        result.map_err(|e| ExcelError::WriteError(e.to_string()))?;
+    }
+
+    workbook
+        .save(output_path)
+        .map_err(|e| ExcelError::WriteError(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("tauri-app-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_anonymize_sheet_keeps_shape() {
+        let input = temp_path("anonymize-input.xlsx");
+        let output = temp_path("anonymize-output.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = [
+            ["学生姓名", "身份证件号", "学校名称"],
+            ["张三", "11010120100307002X", "第一小学"],
+            ["李四", "110101201205060018", "第一小学"],
+            ["张三", "11010120100307002X", "第二小学"],
+        ];
+        for (r, [name, id, school]) in rows.iter().enumerate() {
+            worksheet.write_string(r as u32, 0, *name).unwrap();
+            worksheet.write_string(r as u32, 1, *id).unwrap();
+            worksheet.write_string(r as u32, 4, *school).unwrap();
+        }
+        let date_format = Format::new().set_num_format("yyyy-mm-dd");
+        worksheet
+            .write_number_with_format(1, 5, 45123.0, &date_format)
+            .unwrap();
+        workbook.save(&input).unwrap();
+
+        anonymize_sheet(&input, &output, 0).unwrap();
+
+        let original = open_sheet_range(&input, 0).unwrap();
+        let anonymized = open_sheet_range(&output, 0).unwrap();
+        assert_eq!(original.get_size(), anonymized.get_size());
+        assert_eq!(original.start(), anonymized.start());
+        // 日期仍按日期读出, 而不是序列号
+        assert_eq!(anonymized.get_value((1, 5)), original.get_value((1, 5)));
+        assert!(matches!(
+            anonymized.get_value((1, 5)),
+            Some(Data::DateTime(date)) if date.as_f64() == 45123.0
+        ));
+
+        let students = read_student_info(&output).unwrap();
+        assert_eq!(students.len(), 3);
+        for student in &students {
+            assert!(looks_like_id_number(&student.id_number));
+            assert_eq!(
+                id_check_digit(&student.id_number[..17]),
+                student.id_number.chars().last()
+            );
+            assert_ne!(student.name, "张三");
+            assert_ne!(student.name, "李四");
+        }
+        assert_ne!(students[0].id_number, "11010120100307002X");
+        assert_eq!(students[0].id_number, students[2].id_number);
+        assert_eq!(students[0].name, students[2].name);
+        assert_ne!(students[0].id_number, students[1].id_number);
+        assert_eq!(students[0].school.as_deref(), Some("第一小学"));
+
+        let _ = std::fs::remove_file(input);
+        let _ = std::fs::remove_file(output);
+    }
+
+    #[test]
+    fn test_difficulty_type_from_str_round_trip() {
+        for difficulty_type in DifficultyType::all() {
+            let parsed: DifficultyType = difficulty_type.to_string().parse().unwrap();
+            assert_eq!(parsed, difficulty_type);
+        }
+        assert!("脱贫户（继续享受政策）".parse::<DifficultyType>().is_err());
+        assert!("".parse::<DifficultyType>().is_err());
+    }
+
+    #[test]
+    fn test_difficulty_type_code() {
+        let all = DifficultyType::all();
+        let codes: HashSet<&str> = all.iter().map(DifficultyType::code).collect();
+        assert_eq!(codes.len(), all.len());
+
+        for difficulty_type in all {
+            assert_eq!(
+                DifficultyType::from_code(difficulty_type.code()),
+                Some(difficulty_type)
+            );
+        }
+        assert_eq!(
+            DifficultyType::from_code("RURAL_MIN"),
+            Some(DifficultyType::RuralMinimumLiving)
+        );
+        assert_eq!(DifficultyType::from_code("rural_min"), None);
+    }
+
+    #[test]
+    fn test_difficulty_type_from_label() {
+        assert_eq!(
+            DifficultyType::from_label("脱贫户（继续享受政策）"),
+            Some(DifficultyType::PovertyAlleviatedContinuePolicy)
+        );
+        assert_eq!(
+            DifficultyType::from_label(" 防返贫监测对象(风险已消除） "),
+            Some(DifficultyType::AntiPovertyMonitoringRiskEliminated)
+        );
+        assert_eq!(
+            DifficultyType::from_label("农村低保"),
+            Some(DifficultyType::RuralMinimumLiving)
+        );
+        assert_eq!(DifficultyType::from_label("低保"), None);
+    }
+
+    #[test]
+    fn test_dedup_difficult_people() {
+        let person = |id: &str, difficulty_type| DifficultPerson {
+            id_number: id.to_string(),
+            raw_id_number: id.to_string(),
+            difficulty_type,
+            name: None,
+        };
+        let people = vec![
+            person("2", DifficultyType::RuralMinimumLiving),
+            person("1", DifficultyType::RuralMinimumLiving),
+            person("2", DifficultyType::RuralMinimumLiving),
+            person("2", DifficultyType::UrbanMinimumLiving),
+        ];
+
+        let deduped = dedup_difficult_people(people);
+        let keys: Vec<_> = deduped
+            .iter()
+            .map(|p| (p.id_number.as_str(), p.difficulty_type))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("2", DifficultyType::RuralMinimumLiving),
+                ("1", DifficultyType::RuralMinimumLiving),
+                ("2", DifficultyType::UrbanMinimumLiving),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup_match_results() {
+        let result = |name: &str, id: &str, difficulty_type| MatchResult {
+            student: Student {
+                name: name.to_string(),
+                raw_name: name.to_string(),
+                id_number: id.to_string(),
+                student_id: None,
+                class: None,
+                grade: None,
+                school: None,
+                incomplete: false,
+            },
+            difficult_info: DifficultPerson {
+                id_number: id.to_string(),
+                raw_id_number: id.to_string(),
+                difficulty_type,
+                name: None,
+            },
+            match_kind: MatchKind::ExactId,
+        };
+        // 两个来源名册有重叠
+        let first = vec![
+            result("张三", "1", DifficultyType::RuralMinimumLiving),
+            result("李四", "2", DifficultyType::RuralMinimumLiving),
+        ];
+        let second = vec![
+            result("张三(重复)", "1", DifficultyType::RuralMinimumLiving),
+            result("张三", "1", DifficultyType::DisabledWithCertificate),
+            result("李四(重复)", "2", DifficultyType::RuralMinimumLiving),
+        ];
+
+        let deduped = dedup_match_results(first.into_iter().chain(second).collect());
+        let keys: Vec<_> = deduped
+            .iter()
+            .map(|m| (m.student.name.as_str(), m.difficult_info.difficulty_type))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("张三", DifficultyType::RuralMinimumLiving),
+                ("李四", DifficultyType::RuralMinimumLiving),
+                ("张三", DifficultyType::DisabledWithCertificate),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_students_with_difficulty_full() {
+        let students = vec![Student {
+            name: "张三".to_string(),
+            raw_name: "张三".to_string(),
+            id_number: "11010120100307002X".to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        }];
+        let difficult_people = vec![
+            DifficultPerson {
+                id_number: "11010120100307002X".to_string(),
+                raw_id_number: "11010120100307002X".to_string(),
+                difficulty_type: DifficultyType::LowIncomePopulation,
+                name: None,
+            },
+            DifficultPerson {
+                id_number: "110101201205060018".to_string(),
+                raw_id_number: "110101201205060018".to_string(),
+                difficulty_type: DifficultyType::LowIncomePopulation,
+                name: None,
+            },
+        ];
+
+        let outcome = match_students_with_difficulty_full(&students, &difficult_people);
+        assert_eq!(outcome.matched.len(), 1);
+        assert_eq!(outcome.matched[0].student.name, "张三");
+        assert_eq!(outcome.unmatched.len(), 1);
+        assert_eq!(outcome.unmatched[0].id_number, "110101201205060018");
+    }
+
+    #[test]
+    fn test_read_worksheet_infos() {
+        let path = temp_path("worksheets.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("名单").unwrap();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(2, 3, "张三").unwrap();
+        workbook.add_worksheet().set_name("说明").unwrap();
+        workbook.save(&path).unwrap();
+
+        let infos = read_worksheet_infos(&path).unwrap();
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].name, "名单");
+        assert_eq!((infos[0].row_count, infos[0].column_count), (3, 4));
+        assert_eq!(infos[1].index, 1);
+        assert_eq!((infos[1].row_count, infos[1].column_count), (0, 0));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_minimum_living_readers_return_err_on_bad_file() {
+        let missing = temp_path("missing.xls");
+        assert!(
+            DifficultyType::RuralMinimumLiving
+                .reader()
+                .read_with_progress(&missing, false, &|_| true)
+                .is_err()
+        );
+        assert!(
+            DifficultyType::UrbanMinimumLiving
+                .reader()
+                .read_with_progress(&missing, false, &|_| true)
+                .is_err()
+        );
+
+        let invalid = temp_path("invalid.xls");
+        std::fs::write(&invalid, "not an excel file").unwrap();
+        assert!(
+            DifficultyType::RuralMinimumLiving
+                .reader()
+                .read_with_progress(&invalid, false, &|_| true)
+                .is_err()
+        );
+        assert!(
+            DifficultyType::UrbanMinimumLiving
+                .reader()
+                .read_with_progress(&invalid, false, &|_| true)
+                .is_err()
+        );
+
+        let _ = std::fs::remove_file(invalid);
+    }
+
+    #[test]
+    fn test_read_multi_column() {
+        let path = temp_path("multi-column.xlsx");
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "户主身份证号").unwrap();
+        worksheet.write_string(1, 0, "11010120100307002X").unwrap();
+        worksheet.write_string(1, 2, "110101201205060018").unwrap();
+        worksheet.write_string(2, 2, "110101201205060018").unwrap();
+        workbook.save(&path).unwrap();
+
+        let config = MultiColumnConfig {
+            sheet_index: 1,
+            skip_rows: 1,
+            id_columns: vec![0, 2],
+        };
+        let people = read_multi_column(&path, &config, DifficultyType::UrbanMinimumLiving).unwrap();
+        assert_eq!(people.len(), 2);
+        assert!(people.iter().all(|p| p.name.is_none()));
+
+        // 识别到姓名表头时, 户主带姓名, 家庭成员不带
+        let named_path = temp_path("multi-column-named.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "户主身份证号").unwrap();
+        worksheet.write_string(0, 1, "户主姓名").unwrap();
+        worksheet.write_string(0, 2, "成员身份证号").unwrap();
+        worksheet.write_string(1, 0, "11010120100307002X").unwrap();
+        worksheet.write_string(1, 1, "张 三").unwrap();
+        worksheet.write_string(1, 2, "110101201205060018").unwrap();
+        workbook.save(&named_path).unwrap();
+        let config = MultiColumnConfig {
+            sheet_index: 0,
+            ..config
+        };
+        let people =
+            read_multi_column(&named_path, &config, DifficultyType::UrbanMinimumLiving).unwrap();
+        assert_eq!(people[0].name.as_deref(), Some("张三"));
+        assert_eq!(people[1].name, None);
+        let _ = std::fs::remove_file(named_path);
+
+        let config = DifficultyType::UrbanMinimumLiving
+            .multi_column_config()
+            .unwrap();
+        assert_eq!(config.id_columns, [6, 16, 18, 20, 22, 24]);
+        assert!(
+            DifficultyType::LowIncomePopulation
+                .multi_column_config()
+                .is_none()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_selftest_difficulty_file() {
+        let path = temp_path("selftest.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "序号").unwrap();
+        worksheet.write_string(0, 3, "身份证号").unwrap();
+        worksheet.write_string(1, 3, "11010120100307002X").unwrap();
+        worksheet.write_string(2, 3, "110101201001010011").unwrap();
+        workbook.save(&path).unwrap();
+
+        let report = selftest_difficulty_file(&path, DifficultyType::LowIncomePopulation).unwrap();
+        assert_eq!(report.columns.len(), 1);
+        assert_eq!(report.columns[0].column_letter, "D");
+        assert_eq!(report.columns[0].id_count, 2);
+        assert!(report.empty_columns.is_empty());
+        assert_eq!(report.total_ids, 2);
+
+        // 身份证号右移一列后, 模板列读出的是姓名
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "序号").unwrap();
+        worksheet.write_string(1, 3, "张三").unwrap();
+        worksheet.write_string(1, 4, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        let report = selftest_difficulty_file(&path, DifficultyType::LowIncomePopulation).unwrap();
+        assert_eq!(report.columns[0].non_empty_count, 1);
+        assert_eq!(report.columns[0].id_count, 0);
+        assert_eq!(report.empty_columns, ["工作表 1 的 D 列"]);
+        assert_eq!(report.total_ids, 0);
+
+        // 多列模板逐列统计, 只有户主列有数据
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "城镇低保名册").unwrap();
+        worksheet.write_string(2, 6, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        let report = selftest_difficulty_file(&path, DifficultyType::UrbanMinimumLiving).unwrap();
+        assert_eq!(report.columns.len(), 6);
+        assert_eq!(report.columns[0].id_count, 1);
+        assert_eq!(report.empty_columns.len(), 5);
+        assert_eq!(report.empty_columns[0], "工作表 2 的 Q 列");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_extract_id_columns_parallel_keeps_order() {
+        let rows: Vec<Vec<Data>> = (0..2000)
+            .map(|i| vec![Data::String(format!("A{}", i)), Data::Empty, Data::Int(i)])
+            .collect();
+        let rows: Vec<&[Data]> = rows.iter().map(Vec::as_slice).collect();
+
+        assert_eq!(
+            extract_id_columns_parallel(&rows, &[0, 1, 2]),
+            extract_id_columns_sequential(&rows, &[0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn test_read_orphans_xlsx() {
+        let write_sheet = |worksheet: &mut rust_xlsxwriter::Worksheet, id: &str| {
+            worksheet
+                .write_string(0, 0, "孤儿及事实无人抚养儿童发放花名册")
+                .unwrap();
+            worksheet.write_string(1, 0, "序号").unwrap();
+            worksheet.write_string(1, 1, "姓名").unwrap();
+            worksheet.write_string(1, 2, "身份证号码").unwrap();
+            worksheet.write_string(2, 0, "1").unwrap();
+            worksheet.write_string(2, 1, "张三").unwrap();
+            worksheet.write_string(2, 2, id).unwrap();
+        };
+
+        // 只有一个工作表时跳过缺失的第 3 个工作表
+        let single = temp_path("orphans-single.xlsx");
+        let mut workbook = Workbook::new();
+        write_sheet(workbook.add_worksheet(), "11010120100307002X");
+        workbook.save(&single).unwrap();
+
+        let people = DifficultyType::OrphansAndFactuallyUnsupportedChildren
+            .reader()
+            .read_with_progress(&single, false, &|_| true)
+            .unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].id_number, "11010120100307002X");
+
+        let full = temp_path("orphans-full.xlsx");
+        let mut workbook = Workbook::new();
+        write_sheet(workbook.add_worksheet(), "11010120100307002X");
+        write_sheet(workbook.add_worksheet(), "110101201001010011");
+        write_sheet(workbook.add_worksheet(), "110101201205060018");
+        workbook.save(&full).unwrap();
+
+        let people = DifficultyType::OrphansAndFactuallyUnsupportedChildren
+            .reader()
+            .read_with_progress(&full, false, &|_| true)
+            .unwrap();
+        let ids: Vec<&str> = people.iter().map(|p| p.id_number.as_str()).collect();
+        assert_eq!(ids, ["11010120100307002X", "110101201205060018"]);
+
+        let sheets = OrphanSheets::Indices(vec![0, 1, 2]);
+        let people = read_orphans_with_sheets(&full, &sheets, false, &|_| true).unwrap();
+        assert_eq!(people.len(), 3);
+
+        // 按工作表名称查找, 重复的身份证号只保留一条
+        let named = temp_path("orphans-named.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("说明").unwrap();
+        worksheet.write_string(0, 0, "填表说明").unwrap();
+        let worksheet = workbook.add_worksheet().set_name("孤儿").unwrap();
+        write_sheet(worksheet, "11010120100307002X");
+        let worksheet = workbook
+            .add_worksheet()
+            .set_name("事实无人抚养儿童")
+            .unwrap();
+        write_sheet(worksheet, "11010120100307002X");
+        workbook.save(&named).unwrap();
+
+        let people =
+            read_orphans_with_sheets(&named, &OrphanSheets::ByName, false, &|_| true).unwrap();
+        assert_eq!(people.len(), 1);
+
+        let _ = std::fs::remove_file(single);
+        let _ = std::fs::remove_file(full);
+        let _ = std::fs::remove_file(named);
+    }
+
+    /// 生成只含一个工作表的最小 ODS 文件, 单元格均为文本
+    fn write_ods(path: &str, rows: &[&[&str]]) {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let rows: String = rows
+            .iter()
+            .map(|row| {
+                let cells: String = row
+                    .iter()
+                    .map(|cell| {
+                        format!(
+                            "<table:table-cell office:value-type=\"string\"><text:p>{}</text:p></table:table-cell>",
+                            cell
+                        )
+                    })
+                    .collect();
+                format!("<table:table-row>{}</table:table-row>", cells)
+            })
+            .collect();
+        let content = format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<office:document-content",
+                " xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\"",
+                " xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\"",
+                " xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\"",
+                " office:version=\"1.2\">",
+                "<office:body><office:spreadsheet><table:table table:name=\"Sheet1\">{}",
+                "</table:table></office:spreadsheet></office:body></office:document-content>"
+            ),
+            rows
+        );
+
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        zip.start_file("mimetype", options).unwrap();
+        zip.write_all(b"application/vnd.oasis.opendocument.spreadsheet")
+            .unwrap();
+        zip.start_file("META-INF/manifest.xml", options).unwrap();
+        zip.write_all(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<manifest:manifest xmlns:manifest=\"urn:oasis:names:tc:opendocument:xmlns:manifest:1.0\">",
+                "<manifest:file-entry manifest:full-path=\"content.xml\" manifest:media-type=\"text/xml\"/>",
+                "</manifest:manifest>"
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        zip.start_file("content.xml", options).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_read_ods() {
+        let roster = temp_path("students.ods");
+        write_ods(
+            &roster,
+            &[
+                &["学生姓名", "身份证号"],
+                &["张三", "11010120100307002x"],
+                &["李四", "110101201001010011"],
+            ],
+        );
+        let students = read_student_info(&roster).unwrap();
+        assert_eq!(students.len(), 2);
+        assert_eq!(students[0].name, "张三");
+        assert_eq!(students[0].id_number, "11010120100307002X");
+        assert_eq!(read_worksheet_infos(&roster).unwrap()[0].name, "Sheet1");
+
+        let low_income = temp_path("low-income.ods");
+        write_ods(
+            &low_income,
+            &[
+                &["序号", "姓名", "性别", "身份证号"],
+                &["1", "张三", "男", "11010120100307002X"],
+            ],
+        );
+        let people =
+            read_difficult_type_table(&low_income, DifficultyType::LowIncomePopulation).unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].id_number, "11010120100307002X");
+
+        let _ = std::fs::remove_file(roster);
+        let _ = std::fs::remove_file(low_income);
+    }
+
+    #[test]
+    fn test_read_incomplete_students() {
+        let path = temp_path("incomplete-students.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        worksheet.write_string(2, 0, "李四").unwrap();
+        worksheet.write_string(3, 0, "王五").unwrap();
+        workbook.save(&path).unwrap();
+
+        assert_eq!(read_student_info(&path).unwrap().len(), 1);
+
+        let config = StudentColumnConfig {
+            include_incomplete: true,
+            ..Default::default()
+        };
+        let students = read_student_info_with_config(&path, &config).unwrap();
+        assert_eq!(students.len(), 3);
+        assert!(!students[0].incomplete);
+        assert!(students[1].incomplete);
+        assert_eq!(students[1].id_number, "");
+        assert!(find_duplicate_students(&students).is_empty());
+
+        // 资料不全的学生不会与空身份证号或同名的困难人员匹配
+        let difficult_people = vec![DifficultPerson {
+            id_number: String::new(),
+            raw_id_number: String::new(),
+            difficulty_type: DifficultyType::LowIncomePopulation,
+            name: Some("李四".to_string()),
+        }];
+        let options = MatchOptions {
+            fuzzy: true,
+            masked: true,
+            ..Default::default()
+        };
+        assert!(
+            match_students_with_difficulty_opts(&students, &difficult_people, &options).is_empty()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_read_xlsm_student_info() {
+        let path = temp_path("students.xlsm");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        let students = read_student_info(&path).unwrap();
+        assert_eq!(students.len(), 1);
+        assert_eq!(students[0].id_number, "11010120100307002X");
+        assert_eq!(count_data_rows(&path, 0).unwrap(), 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_read_upper_case_extension() {
+        assert!(has_extension("名册.XLSX", "xlsx"));
+        assert!(!has_extension("名册.xlsx.bak", "xlsx"));
+        assert!(!has_extension("xlsx", "xlsx"));
+
+        let path = temp_path("备案表.XLSX");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("低收入人口").unwrap();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(0, 3, "身份证号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        worksheet.write_string(1, 3, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        assert_eq!(read_student_info(&path).unwrap().len(), 1);
+        assert_eq!(read_worksheet_infos(&path).unwrap()[0].name, "低收入人口");
+        assert_eq!(
+            detect_difficulty_type(&path),
+            Some(DifficultyType::LowIncomePopulation)
+        );
+        let people = DifficultyType::LowIncomePopulation
+            .reader()
+            .read(&path)
+            .unwrap();
+        assert_eq!(people.len(), 1);
+
+        let csv = temp_path("students.CSV");
+        std::fs::write(&csv, "学生姓名,身份证号\n张三,11010120100307002X\n").unwrap();
+        assert_eq!(read_student_info(&csv).unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(csv);
+    }
+
+    #[test]
+    fn test_mask_sample_value() {
+        assert_eq!(mask_sample_value("张三"), "张*");
+        assert_eq!(mask_sample_value("11010120100307002X"), "1*****");
+        assert_eq!(mask_sample_value(""), "");
+    }
+
+    #[test]
+    fn test_check_id_column_shape() {
+        let person = |id: &str| DifficultPerson {
+            id_number: normalize_id_number(id),
+            raw_id_number: id.to_string(),
+            difficulty_type: DifficultyType::LowIncomePopulation,
+            name: None,
+        };
+
+        assert!(check_id_column_shape("a.xlsx", &[]).is_ok());
+        let people = vec![
+            person("11010120100307002x"),
+            person("110101100307002"),
+            person("110101********002X"),
+            person("张三"),
+        ];
+        assert!(check_id_column_shape("a.xlsx", &people).is_ok());
+
+        let people = vec![person("张三"), person("李四"), person("11010120100307002X")];
+        let error = check_id_column_shape("a.xlsx", &people).unwrap_err();
+        assert!(error.to_string().starts_with("解析 a.xlsx 失败: "));
+        assert!(
+            matches!(&error, ExcelError::ParseError { message, .. } if message.contains("抽样 3 条中有 2 条") && message.contains("\"张*\"、\"李*\"") && !message.contains("张三"))
+        );
+    }
+
+    #[test]
+    fn test_read_student_info_from_txt() {
+        let path = temp_path("students.txt");
+        std::fs::write(
+            &path,
+            "\u{feff}学生姓名\t身份证号\t\t\t学校名称\n张三\t11010120100307002x\n\n李 四\t110101201001010011\t\t\t第一小学\n",
+        )
+        .unwrap();
+
+        let roster = read_student_info_from_txt(&path, '\t').unwrap();
+        assert_eq!(roster.encoding, "UTF-8");
+        let students = roster.students;
+        assert_eq!(students.len(), 2);
+        assert_eq!(students[0].id_number, "11010120100307002X");
+        assert_eq!(students[0].school, None);
+        assert_eq!(students[1].name, "李四");
+        assert_eq!(students[1].school.as_deref(), Some("第一小学"));
+
+        // 按扩展名识别分隔符, 与 Excel 名册走同一读取入口
+        assert_eq!(read_student_info(&path).unwrap().len(), 2);
+        assert_eq!(count_data_rows(&path, 0).unwrap(), 2);
+
+        let csv = temp_path("students.csv");
+        std::fs::write(&csv, "学生姓名,身份证号\n\"张三\",\"11010120100307002X\"\n").unwrap();
+        let students = read_student_info_from_txt(&csv, ',').unwrap().students;
+        assert_eq!(students[0].name, "张三");
+        assert_eq!(read_student_info(&csv).unwrap().len(), 1);
+
+        // 引号内的分隔符和转义引号不影响后续列
+        std::fs::write(
+            &csv,
+            "学生姓名,身份证号,备注,,学校名称\r\n张三,11010120100307002X,\"转入,待核对\",,\"第\"\"一\"\"小学\"\r\n",
+        )
+        .unwrap();
+        let students = read_student_info_from_txt(&csv, ',').unwrap().students;
+        assert_eq!(students.len(), 1);
+        assert_eq!(students[0].id_number, "11010120100307002X");
+        assert_eq!(students[0].school.as_deref(), Some("第\"一\"小学"));
+
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(csv);
+    }
+
+    #[test]
+    fn test_split_delimited_records() {
+        assert_eq!(
+            split_delimited_records("a, \"b,c\" ,\"d\"\"e\"\n\n\"多\n行\",f", ','),
+            [vec!["a", "b,c", "d\"e"], vec![""], vec!["多\n行", "f"],]
+        );
+        assert_eq!(
+            split_delimited_records("a\t\"b\tc\"\r\n", '\t'),
+            [vec!["a", "b\tc"]]
+        );
+        assert!(split_delimited_records("", ',').is_empty());
+    }
+
+    #[test]
+    fn test_read_gbk_text_roster() {
+        let path = temp_path("students-gbk.csv");
+        let content = "学生姓名,身份证号,,,学校名称\n张三,11010120100307002X,,,第一小学\n欧阳娜娜,110101201001010011,,,实验中学\n";
+        let (bytes, _, had_errors) = encoding_rs::GBK.encode(content);
+        assert!(!had_errors);
+        std::fs::write(&path, &bytes).unwrap();
+
+        assert_eq!(detect_text_encoding(&path).unwrap(), "GBK");
+        let roster = read_student_info_from_txt(&path, ',').unwrap();
+        assert_eq!(roster.encoding, "GBK");
+        assert_eq!(roster.students.len(), 2);
+        assert_eq!(roster.students[0].name, "张三");
+        assert_eq!(roster.students[0].school.as_deref(), Some("第一小学"));
+        assert_eq!(roster.students[1].name, "欧阳娜娜");
+        assert_eq!(roster.students[1].school.as_deref(), Some("实验中学"));
+
+        // 按扩展名走通用读取入口时同样能正确解码
+        assert_eq!(read_student_info(&path).unwrap()[1].name, "欧阳娜娜");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_read_simple_id_list() {
+        let path = temp_path("simple-id-list.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "序号").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(0, 2, "姓名").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002x").unwrap();
+        worksheet.write_string(1, 2, "李四").unwrap();
+        worksheet.write_string(2, 1, "").unwrap();
+        worksheet
+            .write_string(3, 1, " 11010120100307002X ")
+            .unwrap();
+        worksheet.write_string(4, 1, "110101201001010011").unwrap();
+        workbook.save(&path).unwrap();
+
+        let people = read_simple_id_list(&path, 1, 1, DifficultyType::LowIncomePopulation).unwrap();
+        let ids: Vec<&str> = people.iter().map(|p| p.id_number.as_str()).collect();
+        assert_eq!(ids, ["11010120100307002X", "110101201001010011"]);
+        assert_eq!(people[0].name.as_deref(), Some("李四"));
+        assert_eq!(people[1].name, None);
+        assert!(
+            people
+                .iter()
+                .all(|p| p.difficulty_type == DifficultyType::LowIncomePopulation)
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_detect_difficulty_type() {
+        let write_title = |name: &str, title: &str| {
+            let path = temp_path(name);
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.add_worksheet();
+            worksheet.write_string(0, 0, title).unwrap();
+            worksheet.write_string(1, 0, "身份证号").unwrap();
+            workbook.save(&path).unwrap();
+            path
+        };
+
+        let urban = write_title("detect-urban.xlsx", "2025年9月份城镇低保全部备案表");
+        assert_eq!(
+            detect_difficulty_type(&urban),
+            Some(DifficultyType::UrbanMinimumLiving)
+        );
+
+        let ambiguous = write_title("detect-ambiguous.xlsx", "脱贫户名单");
+        assert_eq!(detect_difficulty_type(&ambiguous), None);
+        assert_eq!(
+            detect_difficulty_type_candidates(&ambiguous).unwrap(),
+            [
+                DifficultyType::PovertyAlleviatedContinuePolicy,
+                DifficultyType::PovertyAlleviatedNoPolicy,
+            ]
+        );
+
+        let narrowed = write_title("detect-narrowed.xlsx", "脱贫户 (继续享受政策) 名单");
+        assert_eq!(
+            detect_difficulty_type(&narrowed),
+            Some(DifficultyType::PovertyAlleviatedContinuePolicy)
+        );
+
+        for path in [urban, ambiguous, narrowed] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_read_student_info_from_bytes() {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证件号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet
+            .write_string(1, 1, " 11010120100307002x ")
+            .unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let students = read_student_info_from_bytes(&bytes, true).unwrap();
+        assert_eq!(students.len(), 1);
+        assert_eq!(students[0].id_number, "11010120100307002X");
+
+        assert!(read_student_info_from_bytes(b"not an excel file", false).is_err());
+    }
+
+    #[test]
+    fn test_match_students_with_difficulty_fuzzy() {
+        let student = |name: &str, id: &str| Student {
+            name: name.to_string(),
+            raw_name: name.to_string(),
+            id_number: id.to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        };
+        let person = |name: &str, id: &str| DifficultPerson {
+            id_number: id.to_string(),
+            raw_id_number: id.to_string(),
+            difficulty_type: DifficultyType::DisabledWithCertificate,
+            name: Some(name.to_string()),
+        };
+        let students = vec![
+            student("张三", "11010120100307002X"),
+            student("李四", "110101201205060018"),
+            student("李四", "110101201001010011"),
+            student("王五", "110101201101010012"),
+            student("Amy", "110101201101010013"),
+        ];
+        let difficult_people = vec![
+            person("张三", "110101201003070021"),   // 身份证号有误, 姓名唯一
+            person("李四", "110101201205060019"),   // 同名学生不唯一
+            person("王 五", "110101201101010019"),  // 姓名中间有空格
+            person("Ａｍｙ", "110101201101010019"), // 全角字母
+        ];
+
+        let exact = match_students_with_difficulty_opts(
+            &students,
+            &difficult_people,
+            &MatchOptions::default(),
+        );
+        assert!(exact.is_empty());
+
+        let fuzzy = match_students_with_difficulty_opts(
+            &students,
+            &difficult_people,
+            &MatchOptions {
+                fuzzy: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(fuzzy.len(), 3);
+        assert_eq!(fuzzy[0].student.id_number, "11010120100307002X");
+        assert_eq!(fuzzy[0].match_kind, MatchKind::ByName);
+        assert_eq!(fuzzy[1].student.name, "王五");
+        assert_eq!(fuzzy[2].student.name, "Amy");
+
+        // 困难类型表中的姓名读取时即规范化
+        let row = [
+            Data::String("张 三".to_string()),
+            Data::String("Ｌｉｎ　Ｆｅｎｇ".to_string()),
+            Data::String(" ".to_string()),
+        ];
+        assert_eq!(read_name_cell(&row, Some(0)).as_deref(), Some("张三"));
+        assert_eq!(read_name_cell(&row, Some(1)).as_deref(), Some("Lin Feng"));
+        assert_eq!(read_name_cell(&row, Some(2)), None);
+        assert_eq!(read_name_cell(&row, None), None);
+    }
+
+    #[test]
+    fn test_masked_id_matching() {
+        assert!(masked_id_matches(
+            "110101********002X",
+            "11010120100307002X"
+        ));
+        assert!(masked_id_matches(
+            "110101＊＊＊＊＊＊＊＊002X",
+            "11010120100307002X"
+        ));
+        assert!(!masked_id_matches(
+            "110101********0021",
+            "11010120100307002X"
+        ));
+        assert!(!masked_id_matches("110101****002X", "11010120100307002X"));
+        assert!(!masked_id_matches(
+            "11010120100307002X",
+            "11010120100307002X"
+        ));
+
+        let student = |name: &str, id: &str| Student {
+            name: name.to_string(),
+            raw_name: name.to_string(),
+            id_number: id.to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        };
+        let person = |id: &str| DifficultPerson {
+            id_number: id.to_string(),
+            raw_id_number: id.to_string(),
+            difficulty_type: DifficultyType::RuralMinimumLiving,
+            name: None,
+        };
+        let students = vec![
+            student("张三", "110101********002X"),
+            student("李四", "110101********0011"),
+            student("王五", "11010120120202001*"), // 后缀被遮挡, 不走索引
+        ];
+        // 李四的可见部分对应两个不同的身份证号, 不匹配
+        let difficult_people = vec![
+            person("11010120100307002X"),
+            person("110101201101010011"),
+            person("110101201202020011"),
+            person("110101201303030019"),
+        ];
+
+        assert!(
+            match_students_with_difficulty_opts(
+                &students,
+                &difficult_people,
+                &MatchOptions::default()
+            )
+            .is_empty()
+        );
+
+        let results = match_students_with_difficulty_opts(
+            &students,
+            &difficult_people,
+            &MatchOptions {
+                masked: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].student.name, "张三");
+        assert_eq!(results[0].match_kind, MatchKind::Masked);
+        assert_eq!(results[1].student.name, "王五");
+        assert_eq!(results[1].difficult_info.id_number, "110101201202020011");
+
+        // 两名学生的遮挡号码相同且都对应张三的号码, 无法区分, 两人都不匹配
+        let mut students = students;
+        students.push(student("张山", "110101********002X"));
+        let results = match_students_with_difficulty_opts(
+            &students,
+            &difficult_people,
+            &MatchOptions {
+                masked: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].student.name, "王五");
+
+        assert_eq!(
+            masked_index_key("110101********002X"),
+            Some((18, "110101".to_string(), "002X".to_string()))
+        );
+        assert_eq!(masked_index_key("1101**********002X"), None);
+        assert_eq!(masked_index_key("12345"), None);
+    }
+
+    #[test]
+    fn test_match_skips_empty_ids() {
+        let students = vec![Student {
+            name: "张三".to_string(),
+            raw_name: "张三".to_string(),
+            id_number: String::new(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        }];
+        let difficult_people = vec![DifficultPerson {
+            id_number: String::new(),
+            raw_id_number: String::new(),
+            difficulty_type: DifficultyType::LowIncomePopulation,
+            name: None,
+        }];
+
+        assert!(match_students_with_difficulty(&students, &difficult_people).is_empty());
+        assert_eq!(count_matching_ids(&students, &difficult_people), 0);
+    }
+
+    #[test]
+    fn test_check_id_compatibility() {
+        assert_eq!(id_format("11010120100307002X"), IdFormat::Full18);
+        assert_eq!(id_format("110101800307002"), IdFormat::Legacy15);
+        assert_eq!(id_format("110101********002X"), IdFormat::Masked);
+        assert_eq!(id_format("张三"), IdFormat::Other);
+
+        let student = |id: &str| Student {
+            name: "张三".to_string(),
+            raw_name: "张三".to_string(),
+            id_number: id.to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        };
+        let person = |id: &str| DifficultPerson {
+            id_number: id.to_string(),
+            raw_id_number: id.to_string(),
+            difficulty_type: DifficultyType::LowIncomePopulation,
+            name: None,
+        };
+        let full = vec![person("11010120100307002X"), person("110101201001010011")];
+
+        let report = check_id_compatibility(&[student("110101201205060018"), student("")], &full);
+        assert!(report.compatible);
+        assert_eq!(report.student.sample_size, 1);
+        assert_eq!(report.difficulty.dominant, Some(IdFormat::Full18));
+
+        let masked = vec![
+            student("110101********002X"),
+            student("110101********0011"),
+            student("110101201205060018"),
+        ];
+        let report = check_id_compatibility(&masked, &full);
+        assert!(!report.compatible);
+        assert_eq!(report.student.dominant, Some(IdFormat::Masked));
+        assert_eq!(report.student.counts[&IdFormat::Full18], 1);
+        assert!(report.hint.unwrap().contains("遮挡"));
+
+        let report = check_id_compatibility(&[student("110101800307002")], &full);
+        assert!(report.hint.unwrap().contains("15 位"));
+        assert!(!check_id_compatibility(&[], &full).compatible);
+    }
+
+    #[test]
+    fn test_count_matching_ids() {
+        let student = |id: &str| Student {
+            name: "张三".to_string(),
+            raw_name: "张三".to_string(),
+            id_number: id.to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        };
+        let person = |id: &str, difficulty_type| DifficultPerson {
+            id_number: id.to_string(),
+            raw_id_number: id.to_string(),
+            difficulty_type,
+            name: None,
+        };
+        let students = vec![
+            student("11010120100307002X"),
+            student("11010120100307002X"),
+            student("110101201001010011"),
+            student("110101201205060018"),
+        ];
+        let difficult_people = vec![
+            person("11010120100307002X", DifficultyType::RuralMinimumLiving),
+            person("11010120100307002X", DifficultyType::LowIncomePopulation),
+            person("110101201205060018", DifficultyType::RuralMinimumLiving),
+            person("110101199901010011", DifficultyType::RuralMinimumLiving),
+        ];
+
+        assert_eq!(count_matching_ids(&students, &difficult_people), 2);
+        assert_eq!(count_matching_ids(&students, &[]), 0);
+    }
+
+    #[test]
+    fn test_match_legacy_ids() {
+        let students = vec![Student {
+            name: "张三".to_string(),
+            raw_name: "张三".to_string(),
+            id_number: "110101800307002".to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        }];
+        let difficult_people = vec![DifficultPerson {
+            id_number: "110101198003070025".to_string(),
+            raw_id_number: "110101198003070025".to_string(),
+            difficulty_type: DifficultyType::RuralMinimumLiving,
+            name: None,
+        }];
+
+        assert!(
+            match_students_with_difficulty_opts(
+                &students,
+                &difficult_people,
+                &MatchOptions::default()
+            )
+            .is_empty()
+        );
+
+        let results = match_students_with_difficulty_opts(
+            &students,
+            &difficult_people,
+            &MatchOptions {
+                legacy_ids: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].student.id_number, "110101198003070025");
+        assert_eq!(results[0].match_kind, MatchKind::ExactId);
+    }
+
+    #[test]
+    fn test_filter_match_results() {
+        let result = |name: &str, grade: Option<&str>, school: &str| MatchResult {
+            student: Student {
+                name: name.to_string(),
+                raw_name: name.to_string(),
+                id_number: format!("id-{}", name),
+                student_id: None,
+                class: Some("1班".to_string()),
+                grade: grade.map(str::to_string),
+                school: Some(school.to_string()),
+                incomplete: false,
+            },
+            difficult_info: DifficultPerson {
+                id_number: format!("id-{}", name),
+                raw_id_number: format!("id-{}", name),
+                difficulty_type: DifficultyType::LowIncomePopulation,
+                name: None,
+            },
+            match_kind: MatchKind::ExactId,
+        };
+        let matches = vec![
+            result("张三", Some("一年级"), "First School"),
+            result("李四", Some(" 一年级 "), "Second School"),
+            result("王五", None, "first school"),
+        ];
+
+        let names = |filtered: Vec<MatchResult>| -> Vec<String> {
+            filtered.into_iter().map(|m| m.student.name).collect()
+        };
+        assert_eq!(
+            names(filter_match_results(
+                matches.clone(),
+                Some("一年级"),
+                None,
+                None
+            )),
+            ["张三", "李四"]
+        );
+        assert_eq!(
+            names(filter_match_results(
+                matches.clone(),
+                None,
+                None,
+                Some(" FIRST SCHOOL")
+            )),
+            ["张三", "王五"]
+        );
+        assert_eq!(
+            names(filter_match_results(
+                matches.clone(),
+                Some("一年级"),
+                Some("1班"),
+                Some("first school")
+            )),
+            ["张三"]
+        );
+        assert_eq!(filter_match_results(matches, Some(""), None, None).len(), 3);
+    }
+
+    #[test]
+    fn test_sort_match_results() {
+        let result = |name: &str, school: Option<&str>, difficulty_type| MatchResult {
+            student: Student {
+                name: name.to_string(),
+                raw_name: name.to_string(),
+                id_number: format!("id-{}", name),
+                student_id: None,
+                class: None,
+                grade: None,
+                school: school.map(str::to_string),
+                incomplete: false,
+            },
+            difficult_info: DifficultPerson {
+                id_number: format!("id-{}", name),
+                raw_id_number: format!("id-{}", name),
+                difficulty_type,
+                name: None,
+            },
+            match_kind: MatchKind::ExactId,
+        };
+        let mut matches = vec![
+            result("D", None, DifficultyType::LowIncomePopulation),
+            result("C", Some("B校"), DifficultyType::RuralMinimumLiving),
+            result("B", Some("A校"), DifficultyType::LowIncomePopulation),
+            result("A", Some("B校"), DifficultyType::DisabledWithCertificate),
+        ];
+        let names = |matches: &[MatchResult]| -> Vec<String> {
+            matches.iter().map(|m| m.student.name.clone()).collect()
+        };
+
+        sort_match_results(&mut matches, &[]);
+        assert_eq!(names(&matches), ["D", "C", "B", "A"]);
+
+        sort_match_results(&mut matches, &[SortKey::School, SortKey::Name]);
+        assert_eq!(names(&matches), ["B", "A", "C", "D"]);
+
+        sort_match_results(&mut matches, &[SortKey::DifficultyType]);
+        assert_eq!(names(&matches), ["A", "C", "B", "D"]);
+
+        let classes = ["10班", "2班", "一班"];
+        for (m, class) in matches.iter_mut().zip(classes) {
+            m.student.class = Some(class.to_string());
+        }
+        sort_match_results(&mut matches, &[SortKey::Class]);
+        assert_eq!(names(&matches), ["B", "C", "A", "D"]);
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        use std::cmp::Ordering;
+
+        assert_eq!(natural_cmp("一年级", "二年级"), Ordering::Less);
+        assert_eq!(natural_cmp("九年级", "十年级"), Ordering::Less);
+        assert_eq!(natural_cmp("十一年级", "二十年级"), Ordering::Less);
+        assert_eq!(natural_cmp("2班", "10班"), Ordering::Less);
+        assert_eq!(natural_cmp("高一(2)班", "高一(12)班"), Ordering::Less);
+        assert_eq!(natural_cmp("1班", "1班"), Ordering::Equal);
+        assert_ne!(natural_cmp("1班", "一班"), Ordering::Equal);
+        assert_eq!(natural_cmp("A班", "B班"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_mask_id_number() {
+        assert_eq!(mask_id_number("11010120100307002X"), "110101********002X");
+        assert_eq!(mask_id_number("110101100307002"), "110101*****7002");
+        assert_eq!(mask_id_number("12345"), "*****");
+        assert_eq!(mask_id_number(""), "");
+        assert!(masked_id_matches(
+            &mask_id_number("11010120100307002X"),
+            "11010120100307002X"
+        ));
+    }
+
+    #[test]
+    fn test_match_kind_serde() {
+        assert_eq!(
+            serde_json::to_string(&MatchKind::ExactId).unwrap(),
+            r#""exactId""#
+        );
+        assert_eq!(
+            serde_json::to_string(&MatchKind::ByName).unwrap(),
+            r#""byName""#
+        );
+
+        // 旧数据缺少 match_kind 时默认为按身份证号匹配
+        let json = r#"{
+            "student": {"name": "张三", "id_number": "11010120100307002X"},
+            "difficult_info": {"id_number": "11010120100307002X", "difficulty_type": "农村低保"}
+        }"#;
+        let result: MatchResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.match_kind, MatchKind::ExactId);
+    }
+
+    #[test]
+    fn test_merge_student_rosters() {
+        let write_roster = |name: &str, rows: &[(&str, &str, &str)]| {
+            let path = temp_path(name);
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.add_worksheet();
+            worksheet.write_string(0, 0, "学生姓名").unwrap();
+            for (r, (student_name, id, school)) in rows.iter().enumerate() {
+                let r = r as u32 + 1;
+                worksheet.write_string(r, 0, *student_name).unwrap();
+                worksheet.write_string(r, 1, *id).unwrap();
+                if !school.is_empty() {
+                    worksheet.write_string(r, 4, *school).unwrap();
+                }
+            }
+            workbook.save(&path).unwrap();
+            path
+        };
+
+        let first = write_roster(
+            "roster-first.xlsx",
+            &[
+                ("张三", "11010120100307002X", ""),
+                ("李四", "110101201205060018", "第一小学"),
+            ],
+        );
+        let second = write_roster(
+            "roster-second.xlsx",
+            &[
+                ("张三", "11010120100307002X", "第二小学"),
+                ("李四四", "110101201205060018", "第三小学"),
+                ("王五", "110101201001010011", "第二小学"),
+            ],
+        );
+
+        let roster = merge_student_rosters(&[first.clone(), second.clone()]).unwrap();
+        assert_eq!(roster.students.len(), 3);
+        assert_eq!(roster.students[0].school.as_deref(), Some("第二小学"));
+        assert_eq!(roster.students[1].name, "李四");
+        assert_eq!(roster.students[1].school.as_deref(), Some("第一小学"));
+        assert_eq!(roster.conflicting_ids, ["110101201205060018"]);
+
+        let _ = std::fs::remove_file(first);
+        let _ = std::fs::remove_file(second);
+    }
+
+    #[test]
+    fn test_validate_difficulty_file() {
+        let path = temp_path("validate-difficulty.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 3, "身份证号").unwrap();
+        worksheet.write_string(1, 3, "11010120100307002X").unwrap();
+        worksheet.write_string(2, 3, "110101201205060018").unwrap();
+        worksheet.write_string(3, 3, "待核实").unwrap();
+        worksheet.write_string(4, 0, "备注").unwrap();
+        workbook.save(&path).unwrap();
+
+        let report = validate_difficulty_file(&path, DifficultyType::LowIncomePopulation).unwrap();
+        assert!(report.sheet_found);
+        assert_eq!(report.id_columns, vec![3]);
+        assert_eq!(report.data_rows, 4);
+        assert_eq!(report.non_empty_ids, 3);
+        assert_eq!(report.id_shaped_count, 2);
+
+        // 城乡特困数据在第 2 个工作表
+        let report =
+            validate_difficulty_file(&path, DifficultyType::RuralSpecialDifficulty).unwrap();
+        assert!(!report.sheet_found);
+        assert_eq!(report.sheet_count, 1);
+        assert_eq!(report.id_shaped_count, 0);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_validate_difficulty_file_matches_reader() {
+        let id = |n: usize| format!("110101201003070{:03}", n);
+
+        // 城镇低保: 第 2 个工作表, 前两行为标题 (无身份证表头), 户主在 G 列, 家庭成员在 Q/S 列
+        let path = temp_path("validate-urban.xlsx");
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "城镇低保名册").unwrap();
+        worksheet.write_string(1, 0, "序号").unwrap();
+        for row in 2..5u32 {
+            let n = row as usize * 10;
+            worksheet.write_number(row, 0, row as f64).unwrap();
+            worksheet.write_string(row, 6, id(n)).unwrap();
+            worksheet.write_string(row, 16, id(n + 1)).unwrap();
+        }
+        worksheet.write_string(4, 18, id(99)).unwrap();
+        workbook.save(&path).unwrap();
+
+        let report = validate_difficulty_file(&path, DifficultyType::UrbanMinimumLiving).unwrap();
+        let people = read_difficult_type_table(&path, DifficultyType::UrbanMinimumLiving).unwrap();
+        assert_eq!(report.data_rows, 3);
+        assert_eq!(report.id_shaped_count, 7);
+        assert_eq!(report.id_shaped_count, people.len());
+        let _ = std::fs::remove_file(path);
+
+        // 孤儿名册: 第 1 和第 3 个工作表, 前三行为标题, 身份证在 C 列
+        let path = temp_path("validate-orphans.xlsx");
+        let mut workbook = Workbook::new();
+        for sheet in 0..3u32 {
+            let worksheet = workbook.add_worksheet();
+            for row in 0..3u32 {
+                worksheet.write_string(row, 0, "标题").unwrap();
+            }
+            worksheet.write_string(2, 2, "说明").unwrap();
+            for row in 3..5u32 {
+                worksheet.write_number(row, 0, row as f64).unwrap();
+                worksheet
+                    .write_string(row, 2, id((sheet * 10 + row) as usize))
+                    .unwrap();
+            }
+        }
+        workbook.save(&path).unwrap();
+
+        let orphans = DifficultyType::OrphansAndFactuallyUnsupportedChildren;
+        let report = validate_difficulty_file(&path, orphans).unwrap();
+        let people = read_difficult_type_table(&path, orphans).unwrap();
+        assert_eq!(report.sheet_indices, vec![0, 2]);
+        assert_eq!(report.data_rows, 4);
+        assert_eq!(report.non_empty_ids, 4);
+        assert_eq!(report.id_shaped_count, people.len());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_cell_to_id_string() {
+        assert_eq!(
+            cell_to_id_string(&Data::Float(110101100307002.0)),
+            "110101100307002"
+        );
+        assert_eq!(
+            cell_to_id_string(&Data::Float(1.1010120100307e17)),
+            "110101201003070000"
+        );
+        assert_eq!(
+            cell_to_id_string(&Data::Int(110101100307002)),
+            "110101100307002"
+        );
+        assert_eq!(
+            cell_to_id_string(&Data::String(" 11010120100307002X ".to_string())),
+            "11010120100307002X"
+        );
+        assert_eq!(cell_to_id_string(&Data::Empty), "");
+
+        // 以数字格式存储的身份证号也能被读取
+        let path = temp_path("numeric-id.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "序号").unwrap();
+        worksheet.write_string(0, 3, "身份证号").unwrap();
+        worksheet.write_number(1, 3, 110101100307002.0).unwrap();
+        workbook.save(&path).unwrap();
+
+        let people = read_difficult_type_table(&path, DifficultyType::LowIncomePopulation).unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].id_number, "110101100307002");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_find_duplicate_students() {
+        let student = |name: &str, id: &str| Student {
+            name: name.to_string(),
+            raw_name: name.to_string(),
+            id_number: id.to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        };
+        let students = vec![
+            student("张三", "11010120100307002X"),
+            student("李四", "110101201205060018"),
+            student("张三三", "11010120100307002X"),
+        ];
+
+        let duplicates = find_duplicate_students(&students);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].0, "11010120100307002X");
+        assert_eq!(duplicates[0].1.len(), 2);
+
+        // 匹配时以首次出现的记录为准
+        let people = vec![DifficultPerson {
+            id_number: "11010120100307002X".to_string(),
+            raw_id_number: "11010120100307002X".to_string(),
+            difficulty_type: DifficultyType::LowIncomePopulation,
+            name: None,
+        }];
+        let matches = match_students_with_difficulty(&students, &people);
+        assert_eq!(matches[0].student.name, "张三");
+    }
+
+    #[test]
+    fn test_count_data_rows() {
+        let path = temp_path("count-data-rows.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "学生信息表").unwrap();
+        worksheet.write_string(1, 0, "姓名").unwrap();
+        worksheet.write_string(1, 1, "身份证号").unwrap();
+        workbook.save(&path).unwrap();
+        assert_eq!(count_data_rows(&path, 0).unwrap(), 0);
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(3, 0, "李四").unwrap();
+        workbook.save(&path).unwrap();
+        assert_eq!(count_data_rows(&path, 0).unwrap(), 2);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_read_student_info_at() {
+        let path = temp_path("student-info-at.xlsx");
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().write_string(0, 0, "封面").unwrap();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        let config = StudentColumnConfig::default();
+        let students = read_student_info_at(&path, 1, &config).unwrap();
+        assert_eq!(students.len(), 1);
+        assert_eq!(students[0].name, "张三");
+
+        let error = read_student_info_at(&path, 5, &config).unwrap_err();
+        assert!(matches!(
+            &error,
+            ExcelError::SheetNotFound {
+                path: error_path,
+                index: 5,
+                available: 2
+            } if *error_path == path
+        ));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_missing_second_sheet() {
+        let path = temp_path("missing-second-sheet.xlsx");
+        let mut workbook = Workbook::new();
+        workbook
+            .add_worksheet()
+            .write_string(0, 0, "身份证号")
+            .unwrap();
+        workbook.save(&path).unwrap();
+
+        let error =
+            read_difficult_type_table(&path, DifficultyType::RuralMinimumLiving).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("{} 缺少第 2 个工作表 (共 1 个工作表)", path)
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_difficulty_type_layout() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(27), "AB");
+
+        let layout = DifficultyType::UrbanMinimumLiving.layout();
+        assert_eq!(layout.sheet_indices, vec![1]);
+        assert_eq!(layout.data_start_row, 2);
+        assert_eq!(layout.id_columns, vec![6, 16, 18, 20, 22, 24]);
+        assert_eq!(layout.id_column_letters[0], "G");
+
+        let layout = DifficultyType::OrphansAndFactuallyUnsupportedChildren.layout();
+        assert_eq!(layout.sheet_indices, vec![0, 2]);
+        assert_eq!(layout.data_start_row, 3);
+        assert_eq!(layout.id_columns, vec![2]);
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("张 三"), "张三");
+        assert_eq!(normalize_name(" 李\u{3000}四\u{200B} "), "李四");
+        assert_eq!(normalize_name("Li  Ming"), "Li Ming");
+        assert_eq!(normalize_name("买买提·\u{FEFF}艾力"), "买买提·艾力");
+
+        // 分解形式与组合形式规范化后一致
+        assert_eq!(normalize_name("Zoe\u{301}"), normalize_name("Zo\u{E9}"));
+        assert_eq!(normalize_name("Ｌｉ Ming"), "Li Ming");
+    }
+
+    #[test]
+    fn test_normalize_full_width_id() {
+        assert_eq!(
+            normalize_id_number("１１０１０１２０１００３０７００２Ｘ"),
+            "11010120100307002X"
+        );
+        // 罗马数字 Ⅹ 经 NFKC 规范化为 X
+        assert_eq!(
+            normalize_id_number("11010120100307002\u{2169}"),
+            "11010120100307002X"
+        );
+
+        let students = vec![Student {
+            name: "张三".to_string(),
+            raw_name: "张三".to_string(),
+            id_number: "11010120100307002X".to_string(),
+            student_id: None,
+            class: None,
+            grade: None,
+            school: None,
+            incomplete: false,
+        }];
+        let raw = "１１０１０１２０１００３０７００２ｘ";
+        let people = vec![DifficultPerson {
+            id_number: normalize_id_number(raw),
+            raw_id_number: raw.to_string(),
+            difficulty_type: DifficultyType::LowIncomePopulation,
+            name: None,
+        }];
+        assert_eq!(match_students_with_difficulty(&students, &people).len(), 1);
+    }
+
+    #[test]
+    fn test_guess_difficulty_type_from_file_name() {
+        assert_eq!(
+            guess_difficulty_type_from_file_name("2025年9月份农村低保备案表.xls"),
+            Some(DifficultyType::RuralMinimumLiving)
+        );
+        assert_eq!(
+            guess_difficulty_type_from_file_name("/data/监测对象 (风险已消除).xlsx"),
+            Some(DifficultyType::AntiPovertyMonitoringRiskEliminated)
+        );
+        assert_eq!(
+            guess_difficulty_type_from_file_name("城乡特困人员名册.xlsx"),
+            Some(DifficultyType::RuralSpecialDifficulty)
+        );
+        assert_eq!(
+            guess_difficulty_type_from_file_name("持证残疾人.xls"),
+            Some(DifficultyType::DisabledWithCertificate)
+        );
+        assert_eq!(guess_difficulty_type_from_file_name("脱贫户.xlsx"), None);
+        assert_eq!(guess_difficulty_type_from_file_name("名单.xlsx"), None);
+        // 同时提及多个类型时无法确定
+        assert_eq!(
+            guess_difficulty_type_from_file_name("农村低保及低收入人口.xlsx"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_read_cancelled_by_progress_callback() {
+        let path = temp_path("cancelled.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        let result = read_difficult_type_table_with_progress(
+            &path,
+            DifficultyType::DisabledWithCertificate,
+            false,
+            &|_| false,
+        );
+        assert!(matches!(result, Err(ExcelError::Cancelled)));
+
+        let people = read_difficult_type_table_with_progress(
+            &path,
+            DifficultyType::DisabledWithCertificate,
+            false,
+            &|_| true,
+        )
+        .unwrap();
+        assert_eq!(people.len(), 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_strict_column_width() {
+        let path = temp_path("strict-width.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(0, 1, "身份证号").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        workbook.save(&path).unwrap();
+
+        // 低收入人口的身份证号位于 D 列, 该文件只有两列
+        let lenient = read_difficult_type_table_with_progress(
+            &path,
+            DifficultyType::LowIncomePopulation,
+            false,
+            &|_| true,
+        )
+        .unwrap();
+        assert!(lenient.is_empty());
+
+        let error = read_difficult_type_table_with_progress(
+            &path,
+            DifficultyType::LowIncomePopulation,
+            true,
+            &|_| true,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&error, ExcelError::ParseError { message, .. } if message.contains("最多 2 列") && message.contains("至少 4 列"))
+        );
+
+        assert!(
+            read_difficult_type_table_with_progress(
+                &path,
+                DifficultyType::DisabledWithCertificate,
+                true,
+                &|_| true,
+            )
+            .is_ok()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_cell_to_display_string() {
+        assert_eq!(cell_to_display_string(&Data::Empty), None);
+        assert_eq!(
+            cell_to_display_string(&Data::String("  ".to_string())),
+            None
+        );
+        assert_eq!(
+            cell_to_display_string(&Data::Float(7.0)).as_deref(),
+            Some("7")
+        );
+        assert_eq!(
+            cell_to_display_string(&Data::Bool(true)).as_deref(),
+            Some("true")
+        );
+
+        let path = temp_path("student-typed-cells.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let date_format = rust_xlsxwriter::Format::new().set_num_format("yyyy-mm-dd");
+        let date = rust_xlsxwriter::ExcelDateTime::from_ymd(2024, 9, 1).unwrap();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_string(1, 1, "11010120100307002X").unwrap();
+        worksheet.write_number(1, 8, 3.0).unwrap();
+        worksheet
+            .write_datetime_with_format(1, 9, &date, &date_format)
+            .unwrap();
+        workbook.save(&path).unwrap();
+
+        let students = read_student_info(&path).unwrap();
+        assert_eq!(students[0].grade.as_deref(), Some("3"));
+        assert_eq!(students[0].class.as_deref(), Some("2024-09-01"));
+        assert_eq!(students[0].school, None);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_preview_sheet() {
+        let path = temp_path("preview-sheet.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let date_format = rust_xlsxwriter::Format::new().set_num_format("yyyy-mm-dd");
+        let date = rust_xlsxwriter::ExcelDateTime::from_ymd(2012, 5, 6).unwrap();
+        worksheet.write_string(0, 0, "姓名").unwrap();
+        worksheet.write_string(0, 1, "学号").unwrap();
+        worksheet.write_string(0, 2, "入学日期").unwrap();
+        worksheet.write_string(1, 0, "张三").unwrap();
+        worksheet.write_number(1, 1, 2024001.0).unwrap();
+        worksheet
+            .write_datetime_with_format(1, 2, &date, &date_format)
+            .unwrap();
+        worksheet.write_string(2, 0, "李四").unwrap();
+        workbook.save(&path).unwrap();
+
+        let rows = preview_sheet(&path, 0, 2).unwrap();
+        assert_eq!(
+            rows,
+            [
+                ["姓名", "学号", "入学日期"],
+                ["张三", "2024001", "2012-05-06"]
+            ]
+        );
+        assert!(preview_sheet(&path, 3, 2).is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_parse_id_demographics() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+
+        let demographics = parse_id_demographics_at("11010120100307002X", today).unwrap();
+        assert_eq!(
+            demographics.birth_date,
+            NaiveDate::from_ymd_opt(2010, 3, 7).unwrap()
+        );
+        assert_eq!(demographics.gender, Gender::Female);
+        assert_eq!(demographics.age, 13);
+
+        let demographics = parse_id_demographics_at("110101201205060018", today).unwrap();
+        assert_eq!(demographics.gender, Gender::Male);
+        assert_eq!(demographics.age, 11);
+
+        // 15 位旧号码按 19xx 年出生处理
+        assert_eq!(
+            upgrade_legacy_id("110101800307002").as_deref(),
+            Some("110101198003070025")
+        );
+        assert_eq!(canonicalize_id("110101800307002"), "110101198003070025");
+        assert_eq!(canonicalize_id("110101198003070025"), "110101198003070025");
+        assert_eq!(canonicalize_id("11010180030700"), "11010180030700");
+        let demographics = parse_id_demographics_at("110101800307002", today).unwrap();
+        assert_eq!(
+            demographics.birth_date,
+            NaiveDate::from_ymd_opt(1980, 3, 7).unwrap()
+        );
+
+        assert!(parse_id_demographics_at("110101201302300018", today).is_none());
+        assert!(parse_id_demographics_at("abc", today).is_none());
+    }
+
+    #[test]
+    fn test_read_difficult_type_by_sheet_name() {
+        let path = temp_path("combined-workbook.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("低收入人口").unwrap();
+        worksheet.write_string(0, 0, "序号").unwrap();
+        worksheet.write_string(0, 3, "身份证号").unwrap();
+        worksheet.write_string(1, 3, "11010120100307002X").unwrap();
+        let worksheet = workbook.add_worksheet().set_name("城镇低保").unwrap();
+        worksheet.write_string(0, 0, "序号").unwrap();
+        worksheet.write_string(0, 6, "户主身份证号").unwrap();
+        worksheet.write_string(1, 6, "110101201205060018").unwrap();
+        worksheet.write_string(1, 16, "110101201001010011").unwrap();
+        workbook.save(&path).unwrap();
+
+        let people = read_difficult_type_by_sheet_name(
+            &path,
+            "低收入人口",
+            DifficultyType::LowIncomePopulation,
+        )
+        .unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].id_number, "11010120100307002X");
+
+        let people = read_difficult_type_by_sheet_name(
+            &path,
+            "城镇低保",
+            DifficultyType::UrbanMinimumLiving,
+        )
+        .unwrap();
+        assert_eq!(people.len(), 2);
+
+        assert!(
+            read_difficult_type_by_sheet_name(
+                &path,
+                "农村低保",
+                DifficultyType::RuralMinimumLiving
+            )
+            .is_err()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_diff_difficult_people() {
+        let person = |id: &str| DifficultPerson {
+            id_number: id.to_string(),
+            raw_id_number: id.to_string(),
+            difficulty_type: DifficultyType::DisabledWithCertificate,
+            name: None,
+        };
+        let old = vec![person("1"), person("2"), person("2"), person("3")];
+        let new = vec![person("4"), person("3"), person("2")];
+
+        let diff = diff_difficult_people(&old, &new);
+        assert_eq!(diff.added, vec!["4".to_string()]);
+        assert_eq!(diff.removed, vec!["1".to_string()]);
+        assert_eq!(diff.unchanged_count, 2);
+    }
+
+    #[test]
+    fn test_describe_xlsx_error() {
+        let path = temp_path("corrupt.xlsx");
+        std::fs::write(&path, "not a zip archive").unwrap();
+
+        let error = read_student_info(&path).unwrap_err();
+        assert!(
+            matches!(&error, ExcelError::ParseError { message, .. } if message.contains("压缩包"))
+        );
+
+        assert!(matches!(
+            describe_xlsx_error(&path, XlsxError::FileNotFound("xl/workbook.xml".to_string())),
+            ExcelError::ParseError { path: error_path, message } if error_path == path && message.contains("xl/workbook.xml")
+        ));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_workbook_format_from_path() {
+        assert_eq!(
+            WorkbookFormat::from_path("a.XLS"),
+            Some(WorkbookFormat::Xls)
+        );
+        assert_eq!(
+            WorkbookFormat::from_path("a.xlsm"),
+            Some(WorkbookFormat::Xlsx)
+        );
+        assert_eq!(
+            WorkbookFormat::from_path("a.ods"),
+            Some(WorkbookFormat::Ods)
+        );
+        assert_eq!(WorkbookFormat::from_path("a.csv"), None);
+        assert_eq!(WorkbookFormat::from_path("a"), None);
+    }
+
+    #[test]
+    fn test_read_error_context() {
+        assert_eq!(
+            ExcelError::read("名单.xlsx", Some(1), "XML 错误").to_string(),
+            "读取 名单.xlsx 失败 (第 2 个工作表): XML 错误"
+        );
+
+        let path = temp_path("roster.doc");
+        std::fs::write(&path, "not a workbook").unwrap();
+        let error = read_worksheet_infos(&path).unwrap_err();
+        assert!(matches!(
+            &error,
+            ExcelError::ReadError { path: error_path, sheet: None, message }
+                if *error_path == path && message == UNSUPPORTED_FORMAT
+        ));
+        assert!(error.to_string().contains(&path));
+        // 孤儿名册同样不再把未知扩展名当作 xls 读取
+        let error = read_orphans_with_sheets(&path, &OrphanSheets::default(), false, &|_| true)
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            ExcelError::ReadError { message, .. } if message == UNSUPPORTED_FORMAT
+        ));
+        let _ = std::fs::remove_file(path);
+
+        // 批量读取孤儿名册时, 错误中应能看出是哪个文件
+        let path = temp_path("no-orphan-sheets.xlsx");
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("低保").unwrap();
+        workbook.save(&path).unwrap();
+        let error =
+            read_orphans_with_sheets(&path, &OrphanSheets::ByName, false, &|_| true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("读取 {} 失败: {}", path, MISSING_SHEETS)
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_find_header_row() {
+        let mut range = Range::new((0, 0), (4, 2));
+        range.set_value((0, 0), Data::String("城乡特困人员备案表".to_string()));
+        range.set_value((1, 0), Data::String("序号".to_string()));
+        range.set_value((1, 1), Data::String("姓名".to_string()));
+        range.set_value((1, 2), Data::String("身份证号".to_string()));
+        range.set_value((2, 2), Data::String("公民 身份证 号码".to_string()));
+        range.set_value((3, 2), Data::String("11010120100307002X".to_string()));
+        range.set_value((4, 2), Data::String("身份证号".to_string()));
+
+        assert_eq!(find_header_row(&range, &["身份证"]), Some(2));
+        assert_eq!(find_header_row(&range, &["姓名", "身份证"]), Some(1));
+        assert_eq!(find_header_row(&range, &["户主"]), None);
+        assert_eq!(find_data_start_row(&range, 5), 3);
+    }
+
+    #[test]
+    fn test_read_student_info_with_config() {
+        let path = temp_path("student-config.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "花名册").unwrap();
+        worksheet.write_string(1, 0, "身份证件号").unwrap();
+        worksheet.write_string(1, 1, "学校名称").unwrap();
+        worksheet.write_string(1, 2, "学生姓名").unwrap();
+        worksheet.write_string(2, 0, "11010120100307002x").unwrap();
+        worksheet.write_string(2, 1, "第一小学").unwrap();
+        worksheet.write_string(2, 2, "张三").unwrap();
+        workbook.save(&path).unwrap();
+
+        let config = StudentColumnConfig {
+            name: 2,
+            id_number: 0,
+            school: 1,
+            grade: 3,
+            class: 4,
+            student_id: 5,
+            header_row: 1,
+            include_incomplete: false,
+        };
+        let students = read_student_info_with_config(&path, &config).unwrap();
+        assert_eq!(students.len(), 1);
+        assert_eq!(students[0].name, "张三");
+        assert_eq!(students[0].id_number, "11010120100307002X");
+        assert_eq!(students[0].school.as_deref(), Some("第一小学"));
+        assert_eq!(students[0].grade, None);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_read() {
+        // 按各多列模板的布局生成名册: 标题行之后每行一户, 户主及第一个家庭成员各有身份证号
+        for difficulty_type in [
+            DifficultyType::RuralMinimumLiving,
+            DifficultyType::RuralSpecialDifficulty,
+            DifficultyType::UrbanMinimumLiving,
+        ] {
+            let layout = difficulty_type.layout();
+            let path = temp_path(&format!("read-{:?}.xlsx", difficulty_type));
+            let mut workbook = Workbook::new();
+            for _ in 0..layout.sheet_indices[0] {
+                workbook.add_worksheet();
+            }
+            let worksheet = workbook.add_worksheet();
+            for row in 0..layout.data_start_row as u32 {
+                worksheet.write_string(row, 0, "标题").unwrap();
+            }
+            for n in 0..2u32 {
+                let row = layout.data_start_row as u32 + n;
+                worksheet.write_number(row, 0, (n + 1) as f64).unwrap();
+                for (member, &col) in layout.id_columns[..2].iter().enumerate() {
+                    let id = format!("1101012010030700{}{}", n, member);
+                    worksheet.write_string(row, col as u16, id).unwrap();
+                }
+            }
+            workbook.save(&path).unwrap();
+
+            let result = difficulty_type
+                .reader()
+                .read_with_progress(&path, false, &|_| true)
+                .unwrap();
+            assert_eq!(result.len(), 4, "{:?}", difficulty_type);
+            assert!(result.iter().all(|p| p.difficulty_type == difficulty_type));
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_read_orphans() {
+        // 孤儿名册读取第 1 和第 3 个工作表, 前三行为标题
+        let orphans = DifficultyType::OrphansAndFactuallyUnsupportedChildren;
+        let layout = orphans.layout();
+        let path = temp_path("read-orphans.xlsx");
+        let mut workbook = Workbook::new();
+        for sheet in 0..3u32 {
+            let worksheet = workbook.add_worksheet();
+            for row in 0..layout.data_start_row as u32 {
+                worksheet.write_string(row, 0, "标题").unwrap();
+            }
+            for n in 0..2u32 {
+                let row = layout.data_start_row as u32 + n;
+                worksheet.write_number(row, 0, (n + 1) as f64).unwrap();
+                let id = format!("1101012010030700{}{}", sheet, n);
+                worksheet
+                    .write_string(row, layout.id_columns[0] as u16, id)
+                    .unwrap();
+            }
+        }
+        workbook.save(&path).unwrap();
+
+        let result = orphans
+            .reader()
+            .read_with_progress(&path, false, &|_| true)
+            .unwrap();
+        assert_eq!(result.len(), 2 * layout.sheet_indices.len());
+        assert!(
+            result
+                .iter()
+                .all(|p| !p.id_number.starts_with("11010120100307001"))
+        );
+        let _ = std::fs::remove_file(path);
     }
 }
@@ -1,13 +1,31 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { CommandResult, DifficultyType, MatchResult } from "./upload.ts";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  CommandResult,
+  DifficultyType,
+  MatchOptions,
+  MatchResult,
+  Student,
+  StudentColumnConfig,
+} from "./upload.ts";
 
 /**
  * 匹配结果统计信息
  */
 export interface MatchStatistics {
+  /** 参与匹配的学生数, 不含资料不全的学生 */
   total_students: number;
+  /** 资料不全 (缺少身份证号) 的学生数, 不计入匹配率 */
+  incomplete_students?: number;
   total_matches: number;
+  /** 匹配率 (0~1, 保留四位小数) */
+  match_rate: number;
+  /** 按困难类型统计, 后端返回时包含全部类型 (未匹配的为 0) */
   difficulty_type_counts: Partial<Record<DifficultyType, number>>;
+  /** 按年级统计, 缺失记为 "未知" */
+  by_grade: Record<string, number>;
+  /** 按学校统计, 缺失记为 "未知" */
+  by_school: Record<string, number>;
 }
 
 /**
@@ -15,17 +33,393 @@ export interface MatchStatistics {
  * @param studentFilePath 学生信息表文件路径
  * @param difficultyFilePath 困难类型数据表文件路径
  * @param difficultyType 困难类型
+ * @param studentColumnConfig 学生信息表列配置 (缺省时使用默认布局)
+ * @param matchOptions 匹配选项
+ * @param operationId 操作 ID, 传给 {@link cancelCurrentOperation} 可单独取消本次操作
  * @returns 匹配的学生结果列表
  */
 export async function findStudentsByDifficulty(
   studentFilePath: string,
   difficultyFilePath: string,
   difficultyType: string,
+  studentColumnConfig?: StudentColumnConfig,
+  matchOptions?: MatchOptions,
+  operationId?: string,
 ): Promise<CommandResult<MatchResult[]>> {
   return await invoke("find_students_by_difficulty", {
     studentFilePath,
     difficultyFilePath,
     difficultyType,
+    studentColumnConfig,
+    matchOptions,
+    operationId,
+  });
+}
+
+/**
+ * 一页匹配结果及结果总数
+ */
+export interface PagedMatches {
+  items: MatchResult[];
+  total: number;
+}
+
+/**
+ * 分页查找学生信息, 完整结果缓存在后端, 翻页时不重新匹配
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFilePath 困难类型数据表文件路径
+ * @param difficultyType 困难类型
+ * @param offset 起始位置 (从 0 开始)
+ * @param limit 每页条数
+ * @param studentColumnConfig 学生信息表列配置 (缺省时使用默认布局)
+ * @param matchOptions 匹配选项
+ * @returns 当前页的匹配结果及总数
+ */
+export async function findStudentsPaged(
+  studentFilePath: string,
+  difficultyFilePath: string,
+  difficultyType: string,
+  offset: number,
+  limit: number,
+  studentColumnConfig?: StudentColumnConfig,
+  matchOptions?: MatchOptions,
+): Promise<CommandResult<PagedMatches>> {
+  return await invoke("find_students_paged", {
+    studentFilePath,
+    difficultyFilePath,
+    difficultyType,
+    studentColumnConfig,
+    matchOptions,
+    offset,
+    limit,
+  });
+}
+
+/**
+ * 按年级、班级、学校筛选匹配结果 (条件同时满足, 忽略首尾空白和大小写)
+ * @param matches 匹配结果数组
+ * @param grade 年级
+ * @param className 班级
+ * @param school 学校
+ * @returns 筛选后的匹配结果
+ */
+export async function filterMatches(
+  matches: MatchResult[],
+  grade?: string,
+  className?: string,
+  school?: string,
+): Promise<CommandResult<MatchResult[]>> {
+  return await invoke("filter_matches", {
+    matches,
+    grade,
+    class: className,
+    school,
+  });
+}
+
+/**
+ * 读取进度 (通过 `read-progress` 事件推送)
+ */
+export interface ReadProgress {
+  processed_rows: number;
+  total_rows: number;
+}
+
+/**
+ * 根据困难类型查找学生信息, 读取过程中推送进度事件
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFilePath 困难类型数据表文件路径
+ * @param difficultyType 困难类型
+ * @param onProgress 读取进度回调
+ * @param studentColumnConfig 学生信息表列配置 (缺省时使用默认布局)
+ * @param matchOptions 匹配选项
+ * @param operationId 操作 ID, 传给 {@link cancelCurrentOperation} 可单独取消本次操作
+ * @returns 匹配的学生结果列表
+ */
+export async function findStudentsByDifficultyWithProgress(
+  studentFilePath: string,
+  difficultyFilePath: string,
+  difficultyType: string,
+  onProgress: (progress: ReadProgress) => void,
+  studentColumnConfig?: StudentColumnConfig,
+  matchOptions?: MatchOptions,
+  operationId?: string,
+): Promise<CommandResult<MatchResult[]>> {
+  const unlisten = await listen<ReadProgress>(
+    "read-progress",
+    (event) => onProgress(event.payload),
+  );
+  try {
+    return await invoke("find_students_by_difficulty_with_progress", {
+      studentFilePath,
+      difficultyFilePath,
+      difficultyType,
+      studentColumnConfig,
+      matchOptions,
+      operationId,
+    });
+  } finally {
+    unlisten();
+  }
+}
+
+/**
+ * 根据困难类型文件内容自动识别困难类型并查找学生信息
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFilePath 困难类型数据表文件路径
+ * @param operationId 操作 ID, 传给 {@link cancelCurrentOperation} 可单独取消本次操作
+ * @returns 匹配的学生结果列表; 无法唯一识别时返回候选类型的错误信息
+ */
+export async function findStudentsAuto(
+  studentFilePath: string,
+  difficultyFilePath: string,
+  operationId?: string,
+): Promise<CommandResult<MatchResult[]>> {
+  return await invoke("find_students_auto", {
+    studentFilePath,
+    difficultyFilePath,
+    operationId,
+  });
+}
+
+/**
+ * 使用没有固定模板的身份证号清单查找学生信息 (读取第一个工作表)
+ * @param studentFilePath 学生信息表文件路径
+ * @param idListPath 身份证号清单文件路径
+ * @param idColumn 身份证号所在列 (从 0 开始)
+ * @param skipRows 表头占用的行数
+ * @param difficultyType 清单对应的困难类型
+ * @param studentColumnConfig 学生信息表列配置 (缺省时使用默认布局)
+ * @returns 匹配的学生结果列表
+ */
+export async function findStudentsByIdList(
+  studentFilePath: string,
+  idListPath: string,
+  idColumn: number,
+  skipRows: number,
+  difficultyType: DifficultyType,
+  studentColumnConfig?: StudentColumnConfig,
+): Promise<CommandResult<MatchResult[]>> {
+  return await invoke("find_students_by_id_list", {
+    studentFilePath,
+    idListPath,
+    idColumn,
+    skipRows,
+    difficultyType,
+    studentColumnConfig,
+  });
+}
+
+/**
+ * 困难类型文件输入
+ */
+export interface DifficultyFileInput {
+  file_path: string;
+  difficulty_type: DifficultyType;
+}
+
+/**
+ * 同时按多个困难类型文件查找学生信息
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFiles 困难类型文件列表 (路径与困难类型)
+ * @param operationId 操作 ID, 传给 {@link cancelCurrentOperation} 可单独取消本次操作
+ * @returns 合并后的匹配结果列表, 同一学生可对应多个困难类型
+ */
+export async function findStudentsMultiDifficulty(
+  studentFilePath: string,
+  difficultyFiles: DifficultyFileInput[],
+  operationId?: string,
+): Promise<CommandResult<MatchResult[]>> {
+  return await invoke("find_students_multi_difficulty", {
+    studentFilePath,
+    difficultyFiles,
+    operationId,
+  });
+}
+
+/**
+ * 文件夹中单个文件的处理结果
+ */
+export interface FileMatchResult {
+  file_path: string;
+  file_name: string;
+  /** 识别出的困难类型 */
+  difficulty_type?: DifficultyType;
+  /** 读取到的困难人员数 */
+  record_count: number;
+  /** 该文件匹配到的学生数 */
+  match_count: number;
+  /** 未处理的原因, 为空表示已处理 */
+  error?: string;
+}
+
+/**
+ * 按文件夹批量匹配的结果
+ */
+export interface FolderMatchResult {
+  matches: MatchResult[];
+  files: FileMatchResult[];
+}
+
+/**
+ * 文件夹匹配进度 (通过 `folder-progress` 事件推送)
+ */
+export interface FolderProgress {
+  current_file: string;
+  /** 当前文件序号, 从 1 开始 */
+  index: number;
+  total: number;
+}
+
+/**
+ * 对文件夹中的全部困难类型文件进行匹配 (按文件名或内容识别困难类型)
+ * @param studentFilePath 学生信息表文件路径
+ * @param folder 困难类型文件所在文件夹
+ * @param onProgress 开始读取每个文件时的进度回调
+ * @param operationId 操作 ID, 传给 {@link cancelCurrentOperation} 可单独取消本次操作
+ * @returns 合并后的匹配结果及每个文件的处理结果
+ */
+export async function matchFolder(
+  studentFilePath: string,
+  folder: string,
+  onProgress?: (progress: FolderProgress) => void,
+  operationId?: string,
+): Promise<CommandResult<FolderMatchResult>> {
+  const unlisten = await listen<FolderProgress>(
+    "folder-progress",
+    (event) => onProgress?.(event.payload),
+  );
+  try {
+    return await invoke("match_folder", {
+      studentFilePath,
+      folder,
+      operationId,
+    });
+  } finally {
+    unlisten();
+  }
+}
+
+/**
+ * 取消正在进行的匹配或读取操作, 被取消的操作返回错误 "已取消"
+ * @param operationId 开始操作时传入的 ID; 省略时取消全部正在进行的操作
+ * @returns 被取消的操作数
+ */
+export async function cancelCurrentOperation(
+  operationId?: string,
+): Promise<CommandResult<number>> {
+  return await invoke("cancel_current_operation", { operationId });
+}
+
+/**
+ * 统计多个困难类型文件中各类型的困难人员数量 (按身份证号去重, 不需要学生名册)
+ * @param files 困难类型文件列表 (路径与困难类型)
+ * @returns 各困难类型的人数
+ */
+export async function summarizeDifficultyFiles(
+  files: DifficultyFileInput[],
+): Promise<CommandResult<Partial<Record<DifficultyType, number>>>> {
+  return await invoke("summarize_difficulty_files", {
+    files,
+  });
+}
+
+/**
+ * 困难类型模板布局 (索引均从 0 开始)
+ */
+export interface LayoutInfo {
+  difficulty_type: DifficultyType;
+  /** 读取的工作表 */
+  sheet_indices: number[];
+  /** 未识别到表头时的数据开始行 */
+  data_start_row: number;
+  /** 身份证号所在列 */
+  id_columns: number[];
+  /** 对应的 Excel 列名, 如 "G" */
+  id_column_letters: string[];
+}
+
+/**
+ * 获取困难类型模板的预期布局
+ * @param difficultyType 困难类型
+ * @returns 工作表、数据开始行及身份证列
+ */
+export async function getDifficultyTypeLayout(
+  difficultyType: DifficultyType,
+): Promise<CommandResult<LayoutInfo>> {
+  return await invoke("get_difficulty_type_layout", {
+    difficultyType,
+  });
+}
+
+/**
+ * 自检时单个身份证列的读取情况
+ */
+export interface ColumnSelfTest {
+  sheet_index: number;
+  column: number;
+  column_letter: string;
+  /** 非空单元格数 */
+  non_empty_count: number;
+  /** 其中形态为身份证号的数量 */
+  id_count: number;
+}
+
+/**
+ * 困难类型文件自检报告
+ */
+export interface SelfTestReport {
+  difficulty_type: DifficultyType;
+  columns: ColumnSelfTest[];
+  /** 没有读出身份证号的列, 如 "工作表 2 的 Q 列", 多为模板列发生偏移 */
+  empty_columns: string[];
+  /** 去重后的身份证号数量 */
+  total_ids: number;
+}
+
+/**
+ * 按困难类型的模板配置自检文件, 核对各身份证列是否仍能读出号码
+ * @param filePath 困难类型文件路径
+ * @param difficultyType 困难类型
+ * @returns 每列读出的号码数量及没有读出号码的列
+ */
+export async function selftestDifficultyFile(
+  filePath: string,
+  difficultyType: DifficultyType,
+): Promise<CommandResult<SelfTestReport>> {
+  return await invoke("selftest_difficulty_file", {
+    filePath,
+    difficultyType,
+  });
+}
+
+/**
+ * 两期困难名单的差异
+ */
+export interface DifficultyDiff {
+  /** 新名单中新增的身份证号 */
+  added: string[];
+  /** 旧名单中被移除的身份证号 */
+  removed: string[];
+  unchanged_count: number;
+}
+
+/**
+ * 比较新旧两期困难类型文件
+ * @param oldPath 旧名单文件路径
+ * @param newPath 新名单文件路径
+ * @param difficultyType 困难类型
+ * @returns 新增与移除的身份证号
+ */
+export async function diffDifficultyFiles(
+  oldPath: string,
+  newPath: string,
+  difficultyType: DifficultyType,
+): Promise<CommandResult<DifficultyDiff>> {
+  return await invoke("diff_difficulty_files", {
+    oldPath,
+    newPath,
+    difficultyType,
   });
 }
 
@@ -34,20 +428,40 @@ export async function findStudentsByDifficulty(
  * @param studentFilePath 学生信息表文件路径
  * @param difficultyFilePath 困难类型数据表文件路径
  * @param difficultyType 困难类型
+ * @param studentColumnConfig 学生信息表列配置 (缺省时使用默认布局)
+ * @param matchOptions 匹配选项
  * @returns 匹配统计信息
  */
 export async function getStudentsMatchStatistics(
   studentFilePath: string,
   difficultyFilePath: string,
   difficultyType: string,
+  studentColumnConfig?: StudentColumnConfig,
+  matchOptions?: MatchOptions,
 ): Promise<CommandResult<MatchStatistics>> {
   return await invoke("get_students_match_statistics", {
     studentFilePath,
     difficultyFilePath,
     difficultyType,
+    studentColumnConfig,
+    matchOptions,
   });
 }
 
+/**
+ * 空的统计信息
+ */
+function emptyStatistics(): MatchStatistics {
+  return {
+    total_students: 0,
+    total_matches: 0,
+    match_rate: 0,
+    difficulty_type_counts: {} as Partial<Record<DifficultyType, number>>,
+    by_grade: {},
+    by_school: {},
+  };
+}
+
 /**
  * 执行学生查找并获取详细结果
  * @param studentFilePath 学生信息表文件路径
@@ -83,11 +497,7 @@ export async function executeStudentSearch(
     if (!matchResult.success) {
       return {
         matches: [],
-        statistics: {
-          total_students: 0,
-          total_matches: 0,
-          difficulty_type_counts: {} as Partial<Record<DifficultyType, number>>,
-        },
+        statistics: emptyStatistics(),
         success: false,
         error: matchResult.error || "查找学生失败",
       };
@@ -96,11 +506,7 @@ export async function executeStudentSearch(
     if (!statsResult.success) {
       return {
         matches: matchResult.data || [],
-        statistics: {
-          total_students: 0,
-          total_matches: 0,
-          difficulty_type_counts: {} as Partial<Record<DifficultyType, number>>,
-        },
+        statistics: emptyStatistics(),
         success: false,
         error: statsResult.error || "获取统计信息失败",
       };
@@ -108,22 +514,13 @@ export async function executeStudentSearch(
 
     return {
       matches: matchResult.data || [],
-      statistics: statsResult.data ||
-        {
-          total_students: 0,
-          total_matches: 0,
-          difficulty_type_counts: {} as Partial<Record<DifficultyType, number>>,
-        },
+      statistics: statsResult.data || emptyStatistics(),
       success: true,
     };
   } catch (error) {
     return {
       matches: [],
-      statistics: {
-        total_students: 0,
-        total_matches: 0,
-        difficulty_type_counts: {} as Partial<Record<DifficultyType, number>>,
-      },
+      statistics: emptyStatistics(),
       success: false,
       error: `执行查找时发生错误: ${error}`,
     };
@@ -157,18 +554,295 @@ export function validateSearchParameters(
   return { valid: true };
 }
 
+/**
+ * Excel 报告的配色, 颜色为 "#RRGGBB" 格式
+ */
+export interface ExportTheme {
+  /** 标题行背景色, 默认 "#4CAF50" */
+  header_bg?: string;
+  /** 标题行文字颜色, 默认 "#FFFFFF" */
+  header_fg?: string;
+}
+
+/** 导出明细的排序字段, 缺失的字段排在最后 */
+export type SortKey = "school" | "grade" | "class" | "name" | "difficultyType";
+
+/**
+ * 检查输出路径所在目录是否存在且可写, 可在耗时的匹配开始前调用
+ * @param path 计划写入的文件路径
+ * @returns 检查结果, 不可写时返回错误信息
+ */
+export async function checkOutputWritable(
+  path: string,
+): Promise<CommandResult<null>> {
+  return await invoke("check_output_writable", {
+    path,
+  });
+}
+
 /**
  * 导出匹配结果到 Excel 文件
  * @param matches 匹配结果数组
  * @param outputPath 输出文件路径
- * @returns 导出结果
+ * @param groupByType 是否按困难类型分工作表导出
+ * @param appendTimestamp 是否在文件名后追加时间戳, 避免覆盖已有报告
+ * @param showRawId 是否额外输出困难名册中的原始身份证号
+ * @param showDemographics 是否额外输出由身份证号解析的年龄和性别
+ * @param maskIds 是否遮挡身份证号中间 8 位 (保留前 6 位和后 4 位)
+ * @param theme 标题行配色, 无效的颜色回退为默认值
+ * @param sortBy 明细排序字段, 依次比较; 不传时保持读取顺序
+ * @param incompleteStudents 资料不全的学生, 非空时单独输出 "资料不全" 工作表
+ * @returns 实际写入的文件路径
  */
 export async function exportMatchesToExcel(
   matches: MatchResult[],
   outputPath: string,
+  groupByType?: boolean,
+  appendTimestamp?: boolean,
+  showRawId?: boolean,
+  showDemographics?: boolean,
+  maskIds?: boolean,
+  theme?: ExportTheme,
+  sortBy?: SortKey[],
+  incompleteStudents?: Student[],
 ): Promise<CommandResult<string>> {
   return await invoke("export_matches_to_excel", {
     matches,
     outputPath,
+    groupByType,
+    appendTimestamp,
+    showRawId,
+    showDemographics,
+    maskIds,
+    theme,
+    sortBy,
+    incompleteStudents,
+  });
+}
+
+/**
+ * 使用系统默认程序打开文件 (如导出后的报告)
+ * @param path 文件路径
+ * @returns 打开结果
+ */
+export async function openPathInDefaultApp(
+  path: string,
+): Promise<CommandResult<null>> {
+  return await invoke("open_path_in_default_app", {
+    path,
+  });
+}
+
+/**
+ * 在后端重新执行匹配并直接导出 Excel 报告 (不经由前端传输匹配列表)
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFilePath 困难类型数据表文件路径
+ * @param difficultyType 困难类型
+ * @param outputPath 输出文件路径
+ * @param studentColumnConfig 学生信息表列配置; 保留资料不全的行时, 报告中单独列出
+ * @returns 导出结果
+ */
+export async function exportMatchesFromFiles(
+  studentFilePath: string,
+  difficultyFilePath: string,
+  difficultyType: string,
+  outputPath: string,
+  studentColumnConfig?: StudentColumnConfig,
+): Promise<CommandResult<string>> {
+  return await invoke("export_matches_from_files", {
+    studentFilePath,
+    difficultyFilePath,
+    difficultyType,
+    outputPath,
+    studentColumnConfig,
+  });
+}
+
+/**
+ * 导出匹配结果到 CSV 文件 (UTF-8 带 BOM)
+ * @param matches 匹配结果数组
+ * @param outputPath 输出文件路径
+ * @param maskIds 是否遮挡身份证号中间 8 位
+ * @returns 导出结果
+ */
+export async function exportMatchesToCsv(
+  matches: MatchResult[],
+  outputPath: string,
+  maskIds?: boolean,
+): Promise<CommandResult<string>> {
+  return await invoke("export_matches_to_csv", {
+    matches,
+    outputPath,
+    maskIds,
+  });
+}
+
+/**
+ * 导出匹配结果到 JSON 文件
+ * @param matches 匹配结果数组
+ * @param outputPath 输出文件路径
+ * @param pretty 是否格式化输出 (缩进换行)
+ * @param maskIds 是否遮挡身份证号中间 8 位
+ * @returns 导出的文件路径
+ */
+export async function exportMatchesToJson(
+  matches: MatchResult[],
+  outputPath: string,
+  pretty: boolean,
+  maskIds?: boolean,
+): Promise<CommandResult<string>> {
+  return await invoke("export_matches_to_json", {
+    matches,
+    outputPath,
+    pretty,
+    maskIds,
+  });
+}
+
+/**
+ * 导出文件格式
+ */
+export type ExportFormat = "xlsx" | "csv" | "json";
+
+/**
+ * 一次导出多种格式, 依次写出 `{outputBase}.{扩展名}`
+ * @param matches 匹配结果列表
+ * @param outputBase 输出路径 (不含扩展名)
+ * @param formats 导出格式, 某个格式失败时错误信息中会列出已成功导出的文件
+ * @returns 所有写出的文件路径
+ */
+export async function exportMatchesMulti(
+  matches: MatchResult[],
+  outputBase: string,
+  formats: ExportFormat[],
+): Promise<CommandResult<string[]>> {
+  return await invoke("export_matches_multi", {
+    matches,
+    outputBase,
+    formats,
+  });
+}
+
+/**
+ * 保存命名的学生信息表列配置方案, 同名方案会被覆盖
+ * @param name 方案名称, 如 "2025秋季格式"
+ * @param config 列配置
+ */
+export async function saveMappingProfile(
+  name: string,
+  config: StudentColumnConfig,
+): Promise<CommandResult<null>> {
+  return await invoke("save_mapping_profile", { name, config });
+}
+
+/**
+ * 读取所有已保存的列配置方案 (按保存顺序)
+ * @returns [方案名称, 列配置] 列表, 尚未保存过时为空列表
+ */
+export async function loadMappingProfiles(): Promise<
+  CommandResult<[string, StudentColumnConfig][]>
+> {
+  return await invoke("load_mapping_profiles");
+}
+
+/**
+ * 估算匹配数量 (名册与困难类型表身份证号的交集大小), 比完整匹配更快
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFilePath 困难类型数据表文件路径
+ * @param difficultyType 困难类型
+ * @param operationId 操作 ID, 传给 {@link cancelCurrentOperation} 可单独取消本次操作
+ * @returns 预计匹配到的学生数
+ */
+export async function estimateMatches(
+  studentFilePath: string,
+  difficultyFilePath: string,
+  difficultyType: string,
+  operationId?: string,
+): Promise<CommandResult<number>> {
+  return await invoke("estimate_matches", {
+    studentFilePath,
+    difficultyFilePath,
+    difficultyType,
+    operationId,
+  });
+}
+
+/**
+ * 仅导出统计信息 (学生总数、匹配数、匹配率及各困难类型数量) 到 Excel 文件
+ * @param stats 匹配统计信息
+ * @param outputPath 输出文件路径
+ * @returns 导出的文件路径
+ */
+export async function exportStatisticsToExcel(
+  stats: MatchStatistics,
+  outputPath: string,
+): Promise<CommandResult<string>> {
+  return await invoke("export_statistics_to_excel", {
+    stats,
+    outputPath,
+  });
+}
+
+/**
+ * 按学校分别导出匹配结果, 每个学校一个 Excel 文件 (学校为空的记录归入 "未分配学校")
+ * @param matches 匹配结果数组
+ * @param outputDir 输出文件夹
+ * @returns 写入的文件路径列表
+ */
+export async function exportMatchesBySchool(
+  matches: MatchResult[],
+  outputDir: string,
+): Promise<CommandResult<string[]>> {
+  return await invoke("export_matches_by_school", {
+    matches,
+    outputDir,
+  });
+}
+
+/**
+ * 身份证号形态
+ */
+export type IdFormat = "full18" | "legacy15" | "masked" | "other";
+
+/**
+ * 一组身份证号的形态统计
+ */
+export interface IdFormatSummary {
+  /** 抽样数量 */
+  sample_size: number;
+  /** 各形态的数量 */
+  counts: Partial<Record<IdFormat, number>>;
+  /** 数量最多的形态, 抽样为空时为空 */
+  dominant?: IdFormat;
+}
+
+/**
+ * 名册与困难类型表的身份证号形态对比结果
+ */
+export interface CompatibilityReport {
+  student: IdFormatSummary;
+  difficulty: IdFormatSummary;
+  /** 两侧主要形态一致 */
+  compatible: boolean;
+  /** 不一致时的说明 */
+  hint?: string;
+}
+
+/**
+ * 抽样对比名册与困难类型表的身份证号形态, 在匹配前发现格式不一致
+ * @param studentFilePath 学生信息表文件路径
+ * @param difficultyFilePath 困难类型数据表文件路径
+ * @param difficultyType 困难类型
+ * @returns 两侧的形态统计及是否兼容
+ */
+export async function checkIdFormatCompatibility(
+  studentFilePath: string,
+  difficultyFilePath: string,
+  difficultyType: string,
+): Promise<CommandResult<CompatibilityReport>> {
+  return await invoke("check_id_format_compatibility", {
+    studentFilePath,
+    difficultyFilePath,
+    difficultyType,
   });
 }
@@ -6,6 +6,8 @@ export interface CommandResult<T> {
   success: boolean;
   data: T | null;
   error: string | null;
+  /** 命令耗时 (毫秒), 仅部分命令填写 */
+  elapsedMs?: number | null;
 }
 
 export interface FileInfo {
@@ -13,20 +15,62 @@ export interface FileInfo {
   path: string;
   size: number;
   extension: string;
+  /** 第一个工作表的数据行数 */
+  data_row_count?: number;
+  /** 分隔符文本 (.txt/.csv) 识别出的编码, 如 "UTF-8"、"GBK" */
+  encoding?: string | null;
+}
+
+export interface WorksheetInfo {
+  name: string;
+  index: number;
+  row_count: number;
+  column_count: number;
 }
 
 export interface DifficultyTypeOption {
   label: string;
   value: string;
+  /** 稳定代码, 不随显示名称变化, 如 "RURAL_MIN" */
+  code: string;
+}
+
+/**
+ * 学生信息表列配置 (列索引从 0 开始)
+ */
+export interface StudentColumnConfig {
+  name: number;
+  id_number: number;
+  school: number;
+  grade: number;
+  class: number;
+  student_id: number;
+  header_row: number;
+  /** 保留有姓名但缺少身份证号的行, 标记为资料不全 */
+  include_incomplete?: boolean;
 }
 
 export interface Student {
+  /** 姓名 (已清理空白和零宽字符) */
   name: string;
+  /** 原始姓名 */
+  raw_name?: string;
   id_number: string;
   student_id?: string;
   class?: string;
   grade?: string;
   school?: string;
+  /** 资料不全 (缺少身份证号), 不参与匹配 */
+  incomplete?: boolean;
+}
+
+/**
+ * 合并后的学生名册
+ */
+export interface MergedRoster {
+  students: Student[];
+  /** 同一身份证号对应不同姓名 */
+  conflicting_ids: string[];
 }
 
 export type DifficultyType =
@@ -43,27 +87,208 @@ export type DifficultyType =
 
 export interface DifficultPerson {
   id_number: string;
+  /** 原始身份证号 (未规范化) */
+  raw_id_number?: string;
   difficulty_type: DifficultyType;
+  name?: string;
 }
 
+/**
+ * 匹配方式: exactId 为身份证号一致, byName 为按唯一同名学生匹配,
+ * masked 为按遮挡身份证号的可见部分匹配 (后两者需人工复核)
+ */
+export type MatchKind = "exactId" | "byName" | "masked";
+
 export interface MatchResult {
   student: Student;
   difficult_info: DifficultPerson;
+  match_kind: MatchKind;
+}
+
+/**
+ * 匹配选项
+ */
+export interface MatchOptions {
+  /** 身份证号未匹配时, 按姓名进行第二轮匹配 (仅对读出姓名的困难人员有效, 多列模板只有户主带姓名) */
+  fuzzy?: boolean;
+  /** 严格模式: 困难类型表的列数不足模板要求时报错 */
+  strict?: boolean;
+  /** 名册身份证号被部分遮挡时, 按未遮挡部分匹配 */
+  masked?: boolean;
+  /** 匹配前将 15 位旧身份证号升级为 18 位 */
+  legacy_ids?: boolean;
+}
+
+/**
+ * 困难类型表预检结果
+ */
+export interface DifficultyFileReport {
+  /** 模板读取的工作表 (与实际读取时一致) */
+  sheet_indices: number[];
+  sheet_count: number;
+  /** 模板工作表至少有一个存在 */
+  sheet_found: boolean;
+  /** 身份证号所在列, 多列模板含家庭成员列 */
+  id_columns: number[];
+  data_rows: number;
+  non_empty_ids: number;
+  /** 疑似身份证号数量 */
+  id_shaped_count: number;
 }
 
 /**
  * 验证上传的文件
  * @param filePath 文件路径
+ * @param maxSizeBytes 文件大小上限 (字节), 省略时为 50 MB
  * @returns 文件信息
  */
 export async function validateUploadedFile(
   filePath: string,
+  maxSizeBytes?: number,
 ): Promise<CommandResult<FileInfo>> {
   return await invoke("validate_uploaded_file", {
     filePath,
+    maxSizeBytes,
   });
 }
 
+/**
+ * 按困难类型的模板布局预检困难人员文件
+ * @param filePath 困难人员文件路径
+ * @param difficultyType 困难类型
+ * @returns 预检结果 (工作表是否存在、疑似身份证号数量等)
+ */
+export async function validateDifficultyFile(
+  filePath: string,
+  difficultyType: DifficultyType,
+): Promise<CommandResult<DifficultyFileReport>> {
+  return await invoke("validate_difficulty_file", {
+    filePath,
+    difficultyType,
+  });
+}
+
+/**
+ * 读取学生信息表并返回解析后的名册, 用于匹配前预览
+ * @param studentFilePath 学生信息表文件路径
+ * @param studentColumnConfig 学生信息表列配置, 省略时使用默认模板; 保留资料不全的行时带 `incomplete` 标记
+ * @returns 解析后的学生列表
+ */
+export async function getStudents(
+  studentFilePath: string,
+  studentColumnConfig?: StudentColumnConfig,
+): Promise<CommandResult<Student[]>> {
+  return await invoke("get_students", {
+    studentFilePath,
+    studentColumnConfig,
+  });
+}
+
+/**
+ * 合并多个学生信息表 (按身份证号去重)
+ * @param studentFilePaths 学生信息表文件路径列表
+ * @returns 合并后的名册及姓名冲突的身份证号
+ */
+export async function mergeStudentFiles(
+  studentFilePaths: string[],
+): Promise<CommandResult<MergedRoster>> {
+  return await invoke("merge_student_files", {
+    studentFilePaths,
+  });
+}
+
+/**
+ * 检查学生信息表中身份证号重复的记录 (匹配时以首次出现的记录为准)
+ * @param studentFilePath 学生信息表文件路径
+ * @param studentColumnConfig 学生信息表列配置, 省略时使用默认模板
+ * @returns 重复的身份证号及对应的全部记录
+ */
+export async function findDuplicateStudentsInFile(
+  studentFilePath: string,
+  studentColumnConfig?: StudentColumnConfig,
+): Promise<CommandResult<[string, Student[]][]>> {
+  return await invoke("find_duplicate_students_in_file", {
+    studentFilePath,
+    studentColumnConfig,
+  });
+}
+
+/**
+ * 清空后端的学生名册缓存, 在外部修改名册后强制重新读取
+ * @returns 清除的缓存条目数
+ */
+export async function clearRosterCache(): Promise<CommandResult<number>> {
+  return await invoke("clear_roster_cache");
+}
+
+/**
+ * 预览工作表的前若干行 (单元格均转换为显示字符串)
+ * @param filePath 文件路径
+ * @param sheetIndex 工作表索引 (从 0 开始)
+ * @param rows 预览行数
+ * @returns 各行单元格内容
+ */
+export async function previewSheet(
+  filePath: string,
+  sheetIndex: number,
+  rows: number,
+): Promise<CommandResult<string[][]>> {
+  return await invoke("preview_sheet", {
+    filePath,
+    sheetIndex,
+    rows,
+  });
+}
+
+/**
+ * 列出 Excel 文件中的所有工作表
+ * @param filePath 文件路径
+ * @returns 工作表信息列表 (名称、索引、行数、列数)
+ */
+export async function listWorksheets(
+  filePath: string,
+): Promise<CommandResult<WorksheetInfo[]>> {
+  return await invoke("list_worksheets", {
+    filePath,
+  });
+}
+
+/**
+ * 生成匿名化样例文件 (身份证号与姓名均被替换, 表格结构保持不变)
+ * @param inputPath 原始文件路径
+ * @param outputPath 输出文件路径 (xlsx)
+ * @param sheetIndex 工作表索引
+ * @returns 输出文件路径
+ */
+export async function anonymizeFile(
+  inputPath: string,
+  outputPath: string,
+  sheetIndex: number,
+): Promise<CommandResult<string>> {
+  return await invoke("anonymize_file", {
+    inputPath,
+    outputPath,
+    sheetIndex,
+  });
+}
+
+/**
+ * 后端版本与构建信息
+ */
+export interface AppInfo {
+  version: string;
+  /** 目标平台三元组, 如 x86_64-pc-windows-msvc */
+  target: string;
+}
+
+/**
+ * 获取后端版本与构建信息
+ * @returns 版本号与目标平台
+ */
+export async function getAppInfo(): Promise<CommandResult<AppInfo>> {
+  return await invoke("get_app_info");
+}
+
 /**
  * 获取困难类型选项列表
  * @returns 困难类型选项列表
@@ -74,6 +299,19 @@ export async function getDifficultyTypeOptions(): Promise<
   return await invoke("get_difficulty_type_options");
 }
 
+/**
+ * 根据文件名关键字推断困难类型, 用于选择文件后预选下拉框
+ * @param fileName 文件名, 如 "2025年9月份农村低保备案表.xls"
+ * @returns 推断出的困难类型选项, 无法唯一确定时为 null
+ */
+export async function guessDifficultyTypeFromName(
+  fileName: string,
+): Promise<CommandResult<DifficultyTypeOption | null>> {
+  return await invoke("guess_difficulty_type_from_name", {
+    fileName,
+  });
+}
+
 /**
  * 打开文件选择对话框
  * @param title 对话框标题
@@ -82,7 +320,7 @@ export async function getDifficultyTypeOptions(): Promise<
  */
 export async function openFileDialog(
   title: string,
-  extensions: string[] = ["xlsx", "xls"],
+  extensions: string[] = ["xlsx", "xlsm", "xls", "ods"],
 ): Promise<string | null> {
   try {
     const selected = await open({
@@ -133,15 +371,45 @@ export async function saveFileDialog(
 }
 
 /**
- * 格式化文件大小
+ * 字节单位进制: binary 为 1024 进制 (KiB/MiB/...), decimal 为 1000 进制 (KB/MB/...)
+ */
+export type ByteBase = "binary" | "decimal";
+
+/**
+ * 按指定进制格式化文件大小
+ * @param bytes 字节数
+ * @param base 单位进制
+ * @returns 格式化后的文件大小字符串
+ */
+export function formatFileSizeWithBase(bytes: number, base: ByteBase): string {
+  const sizes = base === "binary"
+    ? ["Bytes", "KiB", "MiB", "GiB", "TiB", "PiB"]
+    : ["Bytes", "KB", "MB", "GB", "TB", "PB"];
+  return formatWithUnits(bytes, base === "binary" ? 1024 : 1000, sizes);
+}
+
+/**
+ * 格式化文件大小 (1024 进制, 沿用 KB/MB 标签)
  * @param bytes 字节数
  * @returns 格式化后的文件大小字符串
  */
 export function formatFileSize(bytes: number): string {
+  return formatWithUnits(bytes, 1024, [
+    "Bytes",
+    "KB",
+    "MB",
+    "GB",
+    "TB",
+    "PB",
+  ]);
+}
+
+function formatWithUnits(bytes: number, k: number, sizes: string[]): string {
   if (bytes === 0) return "0 Bytes";
-  const k = 1024;
-  const sizes = ["Bytes", "KB", "MB", "GB"];
-  const i = Math.floor(Math.log(bytes) / Math.log(k));
+  const i = Math.min(
+    Math.floor(Math.log(bytes) / Math.log(k)),
+    sizes.length - 1,
+  );
   return parseFloat((bytes / Math.pow(k, i)).toFixed(2)) + " " + sizes[i];
 }
 
@@ -152,7 +420,7 @@ export function formatFileSize(bytes: number): string {
  */
 export function isExcelFile(fileName: string): boolean {
   const extension = fileName.toLowerCase().split(".").pop();
-  return extension === "xlsx" || extension === "xls";
+  return ["xlsx", "xlsm", "xls", "ods"].includes(extension ?? "");
 }
 
 /**